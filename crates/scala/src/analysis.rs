@@ -0,0 +1,115 @@
+/// Analysis of which WIT types generated bindings actually use.
+///
+/// Types reachable from a generated function (including resource functions)
+/// or from a type the bindings export are used; the types of imported
+/// interfaces that are not reachable only add to the generated output.
+use std::collections::HashSet;
+use wit_bindgen_core::wit_parser::*;
+
+/// Get the types of the imported interfaces among `interfaces` that are not
+/// referenced by any function of `interfaces`, world-level item or exported
+/// type, as `ns:pkg/iface#type`, in declaration order.
+///
+/// `interfaces` are the interfaces bindings are generated for.
+pub fn unused_imported_types(
+    resolve: &Resolve,
+    world_id: WorldId,
+    interfaces: &HashSet<InterfaceId>,
+) -> Vec<String> {
+    imported_type_usage(resolve, world_id, interfaces)
+        .into_iter()
+        .filter(|(_, used)| !used)
+        .map(|(type_name, _)| type_name)
+        .collect()
+}
+
+/// Get the types of the imported interfaces among `interfaces`, as
+/// `ns:pkg/iface#type` in declaration order, with whether they are used in
+/// the sense of [`unused_imported_types`].
+pub fn imported_type_usage(
+    resolve: &Resolve,
+    world_id: WorldId,
+    interfaces: &HashSet<InterfaceId>,
+) -> Vec<(String, bool)> {
+    let world = &resolve.worlds[world_id];
+    let exported: HashSet<InterfaceId> = world
+        .exports
+        .values()
+        .filter_map(|item| match item {
+            WorldItem::Interface { id, .. } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    fn add_function(roots: &mut Vec<Type>, func: &Function) {
+        roots.extend(func.params.iter().map(|(_, ty)| *ty));
+        roots.extend(func.result);
+    }
+
+    let mut roots = Vec::new();
+    for item in world.imports.values().chain(world.exports.values()) {
+        match item {
+            WorldItem::Interface { id, .. } if interfaces.contains(id) => {
+                let iface = &resolve.interfaces[*id];
+                for func in iface.functions.values() {
+                    add_function(&mut roots, func);
+                }
+                if exported.contains(id) {
+                    roots.extend(iface.types.values().map(|id| Type::Id(*id)));
+                }
+            }
+            WorldItem::Interface { .. } => {}
+            WorldItem::Function(func) => add_function(&mut roots, func),
+            WorldItem::Type(id) => roots.push(Type::Id(*id)),
+        }
+    }
+
+    let used = reachable_types(resolve, roots);
+
+    let mut usage = Vec::new();
+    for item in world.imports.values() {
+        let WorldItem::Interface { id, .. } = item else {
+            continue;
+        };
+        if !interfaces.contains(id) || exported.contains(id) {
+            continue;
+        }
+        let Some(interface_name) = resolve.id_of(*id) else {
+            continue;
+        };
+        for (type_name, type_id) in &resolve.interfaces[*id].types {
+            usage.push((format!("{}#{}", interface_name, type_name), used.contains(type_id)));
+        }
+    }
+    usage
+}
+
+/// Get the types referenced by `roots`, directly or through other types.
+fn reachable_types(resolve: &Resolve, roots: Vec<Type>) -> HashSet<TypeId> {
+    let mut used = HashSet::new();
+    let mut pending = roots;
+    while let Some(ty) = pending.pop() {
+        let Type::Id(id) = ty else {
+            continue;
+        };
+        if !used.insert(id) {
+            continue;
+        }
+        match &resolve.types[id].kind {
+            TypeDefKind::Record(record) => pending.extend(record.fields.iter().map(|field| field.ty)),
+            TypeDefKind::Variant(variant) => pending.extend(variant.cases.iter().filter_map(|case| case.ty)),
+            TypeDefKind::Tuple(tuple) => pending.extend(tuple.types.iter().copied()),
+            TypeDefKind::Option(inner)
+            | TypeDefKind::List(inner)
+            | TypeDefKind::FixedSizeList(inner, _)
+            | TypeDefKind::Type(inner) => pending.push(*inner),
+            TypeDefKind::Result(result) => pending.extend(result.ok.iter().chain(result.err.iter()).copied()),
+            TypeDefKind::Handle(Handle::Own(resource) | Handle::Borrow(resource)) => {
+                pending.push(Type::Id(*resource))
+            }
+            TypeDefKind::Future(payload) | TypeDefKind::Stream(payload) => pending.extend(*payload),
+            TypeDefKind::Resource | TypeDefKind::Enum(_) | TypeDefKind::Flags(_) | TypeDefKind::Unknown => {}
+        }
+    }
+    used
+}
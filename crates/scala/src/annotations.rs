@@ -4,6 +4,37 @@
 /// Scala code with the WebAssembly Component Model via scala-wasm runtime.
 use std::fmt::Write as _;
 
+/// Package of the scala-wasm runtime referenced by generated code.
+///
+/// Code is generated against it and relocated to the configured runtime
+/// package afterwards, see [`relocate_runtime`].
+pub const RUNTIME_PACKAGE: &str = "scala.scalajs.wit";
+
+/// Render a string as a Scala string literal, escaping quotes, backslashes
+/// and control characters.
+///
+/// # Example
+/// ```scala
+/// "wasi:io/streams@0.2.0"
+/// ```
+pub fn string_literal(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => write!(&mut output, "\\u{:04x}", c as u32).unwrap(),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
 /// Generate @ComponentImport annotation for importing functions.
 ///
 /// # Example
@@ -13,8 +44,9 @@ use std::fmt::Write as _;
 /// ```
 pub fn component_import(namespace: &str, name: &str) -> String {
     format!(
-        "@scala.scalajs.wit.annotation.WitImport(\"{}\", \"{}\")",
-        namespace, name
+        "@scala.scalajs.wit.annotation.WitImport({}, {})",
+        string_literal(namespace),
+        string_literal(name)
     )
 }
 
@@ -27,8 +59,9 @@ pub fn component_import(namespace: &str, name: &str) -> String {
 /// ```
 pub fn component_export(namespace: &str, name: &str) -> String {
     format!(
-        "@scala.scalajs.wit.annotation.WitExport(\"{}\", \"{}\")",
-        namespace, name
+        "@scala.scalajs.wit.annotation.WitExport({}, {})",
+        string_literal(namespace),
+        string_literal(name)
     )
 }
 
@@ -84,8 +117,27 @@ pub fn component_flags(num_flags: usize) -> String {
 /// ```
 pub fn component_resource_import(namespace: &str, name: &str) -> String {
     format!(
-        "@scala.scalajs.wit.annotation.WitResourceImport(\"{}\", \"{}\")",
-        namespace, name
+        "@scala.scalajs.wit.annotation.WitResourceImport({}, {})",
+        string_literal(namespace),
+        string_literal(name)
+    )
+}
+
+/// Generate @ComponentResourceExport annotation for exporting resource types.
+///
+/// # Example
+/// ```scala
+/// @scala.scalajs.wit.annotation.WitResourceExport("example:state/counters", "counter")
+/// trait Counter {
+///   @WitResourceMethod("increment")
+///   def increment(): Unit
+/// }
+/// ```
+pub fn component_resource_export(namespace: &str, name: &str) -> String {
+    format!(
+        "@scala.scalajs.wit.annotation.WitResourceExport({}, {})",
+        string_literal(namespace),
+        string_literal(name)
     )
 }
 
@@ -111,8 +163,8 @@ pub fn component_resource_constructor() -> &'static str {
 /// ```
 pub fn component_resource_method(name: &str) -> String {
     format!(
-        "@scala.scalajs.wit.annotation.WitResourceMethod(\"{}\")",
-        name
+        "@scala.scalajs.wit.annotation.WitResourceMethod({})",
+        string_literal(name)
     )
 }
 
@@ -127,8 +179,8 @@ pub fn component_resource_method(name: &str) -> String {
 /// ```
 pub fn component_resource_static_method(name: &str) -> String {
     format!(
-        "@scala.scalajs.wit.annotation.WitResourceStaticMethod(\"{}\")",
-        name
+        "@scala.scalajs.wit.annotation.WitResourceStaticMethod({})",
+        string_literal(name)
     )
 }
 
@@ -157,6 +209,30 @@ pub fn component_export_interface() -> &'static str {
     "@scala.scalajs.wit.annotation.WitExportInterface"
 }
 
+/// Generate a Scala.js @JSExport annotation, for `--emit-jsexport`.
+///
+/// # Example
+/// ```scala
+/// @scala.scalajs.wit.annotation.WitExport("wasi:cli/run@0.2.0", "run")
+/// @scala.scalajs.js.annotation.JSExport("run")
+/// def run(): Int
+/// ```
+pub fn js_export(name: &str) -> String {
+    format!("@scala.scalajs.js.annotation.JSExport({})", string_literal(name))
+}
+
+/// Generate a Scala.js @JSExportAll annotation, for `--emit-jsexport`.
+///
+/// # Example
+/// ```scala
+/// @scala.scalajs.wit.annotation.WitResourceExport("example:state/counters", "counter")
+/// @scala.scalajs.js.annotation.JSExportAll
+/// trait Counter { ... }
+/// ```
+pub fn js_export_all() -> &'static str {
+    "@scala.scalajs.js.annotation.JSExportAll"
+}
+
 /// Generate the `= scala.scalajs.wit.native` marker for imported functions.
 ///
 /// This indicates that the function implementation is provided by the runtime.
@@ -164,6 +240,151 @@ pub fn native_marker() -> &'static str {
     "scala.scalajs.wit.native"
 }
 
+/// Remove the Component Model annotations from generated Scala code.
+///
+/// Annotation lines are dropped and the native marker of imported members
+/// becomes `???`, so the code keeps compiling as a plain API skeleton.
+/// Comments and string literals are left as they are.
+///
+/// # Example
+/// ```scala
+/// def read(stream: InputStream, len: Long): scala.scalajs.wit.Result[Array[Byte], StreamError] = ???
+/// ```
+pub fn strip_annotations(content: &str) -> String {
+    let code_lines = lines_starting_in_code(content);
+    let mut output = String::with_capacity(content.len());
+    for (line, in_code) in content.lines().zip(code_lines) {
+        if in_code && line.trim_start().starts_with("@scala.scalajs.wit.annotation.") {
+            continue;
+        }
+        writeln!(&mut output, "{}", line).unwrap();
+    }
+    let native = format!("= {}", native_marker());
+    rewrite_code(&output, |code| code.replace(&native, "= ???"))
+}
+
+/// Move references to the runtime from [`RUNTIME_PACKAGE`] to another
+/// runtime package, using a custom marker for natively implemented members.
+/// Comments and string literals are left as they are.
+///
+/// # Example
+/// ```scala
+/// @scala.scalanative.wit.annotation.WitImport("wasi:io/streams@0.2.0", "read")
+/// def read(len: scala.scalanative.wit.unsigned.ULong): Array[Byte] = scala.scalanative.wit.native
+/// ```
+pub fn relocate_runtime(content: &str, runtime_package: &str, native_marker: &str) -> String {
+    rewrite_code(content, |code| {
+        code.replace(&format!("{}.native", RUNTIME_PACKAGE), native_marker)
+            .replace(&format!("{}.", RUNTIME_PACKAGE), &format!("{}.", runtime_package))
+    })
+}
+
+/// Split Scala source into code and non-code spans, as `(start, end, is_code)`
+/// byte ranges. Comments and string literals are non-code.
+fn code_spans(content: &str) -> Vec<(usize, usize, bool)> {
+    enum State {
+        Code,
+        LineComment,
+        BlockComment,
+        Literal,
+    }
+
+    let bytes = content.as_bytes();
+    let mut spans = Vec::new();
+    let mut state = State::Code;
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match state {
+            State::Code => {
+                // Character literals such as `'"'` are skipped as a whole
+                let char_literal = match (bytes.get(i + 1), bytes.get(i + 2), bytes.get(i + 3)) {
+                    (Some(b'\\'), _, Some(b'\'')) => Some(4),
+                    (Some(_), Some(b'\''), _) => Some(3),
+                    _ => None,
+                };
+                if let (b'\'', Some(len)) = (bytes[i], char_literal) {
+                    spans.push((start, i, true));
+                    spans.push((i, i + len, false));
+                    i += len;
+                    start = i;
+                    continue;
+                }
+                let next = match (bytes[i], bytes.get(i + 1)) {
+                    (b'/', Some(b'/')) => Some(State::LineComment),
+                    (b'/', Some(b'*')) => Some(State::BlockComment),
+                    (b'"', _) => Some(State::Literal),
+                    _ => None,
+                };
+                if let Some(next) = next {
+                    spans.push((start, i, true));
+                    start = i;
+                    state = next;
+                }
+                i += 1;
+            }
+            State::LineComment | State::BlockComment | State::Literal => {
+                let end = match (&state, bytes[i]) {
+                    (State::LineComment, b'\n') => Some(i),
+                    (State::BlockComment, b'*') if bytes.get(i + 1) == Some(&b'/') => Some(i + 2),
+                    (State::Literal, b'"') => Some(i + 1),
+                    _ => None,
+                };
+                if let (State::Literal, b'\\') = (&state, bytes[i]) {
+                    // Skip the escaped character
+                    i += 2;
+                    continue;
+                }
+                match end {
+                    Some(end) => {
+                        spans.push((start, end, false));
+                        start = end;
+                        state = State::Code;
+                        i = end;
+                    }
+                    None => i += 1,
+                }
+            }
+        }
+    }
+    spans.push((start, bytes.len(), matches!(state, State::Code)));
+    spans.retain(|(start, end, _)| start < end);
+    spans
+}
+
+/// Apply `rewrite` to the code of Scala source, leaving comments and string
+/// literals untouched.
+fn rewrite_code(content: &str, rewrite: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(content.len());
+    for (start, end, is_code) in code_spans(content) {
+        let span = &content[start..end];
+        if is_code {
+            output.push_str(&rewrite(span));
+        } else {
+            output.push_str(span);
+        }
+    }
+    output
+}
+
+/// Whether each line of Scala source starts in code rather than inside a
+/// block comment.
+fn lines_starting_in_code(content: &str) -> Vec<bool> {
+    let spans = code_spans(content);
+    let mut span = 0;
+    let mut offset = 0;
+    content
+        .lines()
+        .map(|line| {
+            while span < spans.len() && spans[span].1 <= offset {
+                span += 1;
+            }
+            offset += line.len() + 1;
+            spans.get(span).is_none_or(|(_, _, is_code)| *is_code)
+        })
+        .collect()
+}
+
 /// Generate a complete import function signature with annotation.
 ///
 /// # Example
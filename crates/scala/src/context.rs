@@ -1,8 +1,10 @@
-use crate::{Opts, annotations};
+use crate::{
+    ExportFreestandingAs, GetterStyle, Opts, PackageObjectName, ResourceMethodNameStyle, ResultType, ScalaVersion, SerialVersionUid, StringType, annotations, debug::DebugEvent, interface, stub, world,
+};
 use heck::{ToLowerCamelCase, ToPascalCase, ToSnakeCase};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write as _;
-use wit_bindgen_core::wit_parser::*;
+use wit_bindgen_core::{dealias, wit_parser::*};
 
 /// Format WIT documentation as Scaladoc comments.
 ///
@@ -17,13 +19,35 @@ pub fn format_docs(docs: &Docs) -> String {
 /// Converts WIT documentation strings into properly formatted Scaladoc with
 /// the specified indentation level (number of spaces) and continuation markers.
 pub fn format_docs_with_indent(docs: &Docs, indent: usize) -> String {
+    format_docs_with_stability(docs, &Stability::Unknown, indent)
+}
+
+/// Format WIT documentation as Scaladoc comments, adding a `@since` tag for
+/// items gated with `@since(version = ...)`.
+///
+/// Items without docs still get a doc block when they carry a since-version.
+pub fn format_docs_with_stability(docs: &Docs, stability: &Stability, indent: usize) -> String {
+    let tags: Vec<String> = match stability {
+        Stability::Stable { since, .. } => vec![format!("@since {}", since)],
+        _ => Vec::new(),
+    };
+    format_docs_with_tags(docs, &tags, indent)
+}
+
+/// Format WIT documentation as Scaladoc comments followed by Scaladoc tags
+/// such as `@since` or `@return`.
+///
+/// Tags are separated from the docs by a blank line and produce a doc block
+/// on their own for undocumented items.
+pub fn format_docs_with_tags(docs: &Docs, tags: &[String], indent: usize) -> String {
     let content = docs.contents.as_deref().unwrap_or("").trim();
-    if content.is_empty() {
-        return String::new();
-    }
 
     let mut output = String::new();
-    let lines: Vec<&str> = content.lines().collect();
+    let mut lines: Vec<&str> = content.lines().collect();
+    if !tags.is_empty() && !lines.is_empty() {
+        lines.push("");
+    }
+    lines.extend(tags.iter().map(String::as_str));
 
     if lines.is_empty() {
         return String::new();
@@ -31,11 +55,35 @@ pub fn format_docs_with_indent(docs: &Docs, indent: usize) -> String {
 
     let indent_str = " ".repeat(indent);
 
+    // Fenced code blocks become Scaladoc `{{{ }}}` blocks, keeping the
+    // indentation of their lines relative to the fence
+    let mut rendered = Vec::with_capacity(lines.len());
+    let mut fence_indent = None;
+    for line in &lines {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            rendered.push(if fence_indent.is_some() { "}}}" } else { "{{{" }.to_string());
+            fence_indent = match fence_indent {
+                Some(_) => None,
+                None => Some(line.len() - trimmed.len()),
+            };
+        } else if let Some(fence_indent) = fence_indent {
+            let strip = line
+                .bytes()
+                .take(fence_indent)
+                .take_while(|b| *b == b' ' || *b == b'\t')
+                .count();
+            rendered.push(line[strip..].trim_end().to_string());
+        } else {
+            rendered.push(markdown_to_scaladoc(line));
+        }
+    }
+
     // First line with opening /**
-    writeln!(&mut output, "{}/** {}", indent_str, lines[0]).unwrap();
+    writeln!(&mut output, "{}/** {}", indent_str, rendered[0]).unwrap();
 
     // Subsequent lines with continuation marker
-    for line in &lines[1..] {
+    for line in &rendered[1..] {
         if line.trim().is_empty() {
             writeln!(&mut output, "{} *", indent_str).unwrap();
         } else {
@@ -49,12 +97,320 @@ pub fn format_docs_with_indent(docs: &Docs, indent: usize) -> String {
     output
 }
 
+/// Check whether a type is the given primitive, possibly behind type aliases.
+fn is_primitive(resolve: &Resolve, ty: &Type, primitive: Type) -> bool {
+    match ty {
+        Type::Id(id) => match &resolve.types[*id].kind {
+            TypeDefKind::Type(inner) => is_primitive(resolve, inner, primitive),
+            _ => false,
+        },
+        ty => *ty == primitive,
+    }
+}
+
+/// Get the payload of an `option<T>` whose `T` is a primitive other than
+/// `string`, possibly behind type aliases.
+fn primitive_option_payload(resolve: &Resolve, ty: &Type) -> Option<Type> {
+    let Type::Id(id) = ty else {
+        return None;
+    };
+    match &resolve.types[dealias(resolve, *id)].kind {
+        TypeDefKind::Option(inner) => {
+            let payload = match inner {
+                Type::Id(inner_id) => match resolve.types[dealias(resolve, *inner_id)].kind {
+                    TypeDefKind::Type(payload) => payload,
+                    _ => return None,
+                },
+                payload => *payload,
+            };
+            match payload {
+                Type::Id(_) | Type::String | Type::ErrorContext => None,
+                payload => Some(payload),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Get the name of a typeclass instance in a type's companion object, e.g.
+/// `colorOrdering` for `Ordering[Color]`.
+fn instance_name(type_name: &str, suffix: &str) -> String {
+    let mut chars = type_name.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}{}", first.to_lowercase(), chars.as_str(), suffix),
+        None => suffix.to_lowercase(),
+    }
+}
+
+/// Uppercase the first letter of an identifier.
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Compute a deterministic hash of a type's WIT name and rendered members
+/// (64-bit FNV-1a), so it only changes with the structure.
+///
+/// Used for `serialVersionUID`s and the names of anonymous types.
+fn structural_hash(wit_name: &str, fields: &[String]) -> i64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in std::iter::once(wit_name)
+        .chain(fields.iter().map(String::as_str))
+        .flat_map(|part| part.bytes().chain(std::iter::once(0)))
+    {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash as i64
+}
+
+/// Convert the markdown syntax of a WIT doc line to Scaladoc.
+///
+/// Links like `[text](target)` become `[[target text]]` (a leading `#` of an
+/// anchor target is dropped). Inline code spans are valid Scaladoc already and
+/// are copied verbatim, without converting anything inside them. All other text
+/// is left untouched.
+pub fn markdown_to_scaladoc(line: &str) -> String {
+    let mut output = String::new();
+    let mut rest = line;
+
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '`' => {
+                // Copy the whole code span, including its delimiters
+                let end = rest[1..].find('`').map(|i| i + 2).unwrap_or(rest.len());
+                output.push_str(&rest[..end]);
+                rest = &rest[end..];
+            }
+            '[' => match parse_markdown_link(rest) {
+                Some((text, target, len)) => {
+                    let target = target.strip_prefix('#').unwrap_or(target);
+                    write!(&mut output, "[[{} {}]]", target, text).unwrap();
+                    rest = &rest[len..];
+                }
+                None => {
+                    output.push(c);
+                    rest = &rest[1..];
+                }
+            },
+            _ => {
+                output.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    output
+}
+
+/// Parse a markdown link `[text](target)` at the start of `s`, returning the
+/// text, the target and the length of the link in bytes.
+fn parse_markdown_link(s: &str) -> Option<(&str, &str, usize)> {
+    let text_end = s.find(']')?;
+    let text = &s[1..text_end];
+    let after_text = &s[text_end + 1..];
+    if text.is_empty() || text.contains('[') || !after_text.starts_with('(') {
+        return None;
+    }
+    let target_end = after_text.find(')')?;
+    let target = &after_text[1..target_end];
+    if target.is_empty() || target.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((text, target, text_end + 1 + target_end + 1))
+}
+
+/// Rewrite the flat package clause of a generated file below `base_package`
+/// (`package base.wasi.io`) into package blocks nested below the base
+/// package (`package base` followed by `package wasi { package io { ... } }`).
+///
+/// Files outside of the base package are left as they are.
+pub fn nest_packages(content: &str, base_package: &str) -> String {
+    let Some((first_line, rest)) = content.split_once('\n') else {
+        return content.to_string();
+    };
+    let Some(package) = first_line.strip_prefix("package ") else {
+        return content.to_string();
+    };
+    let nested = if base_package.is_empty() {
+        Some(package)
+    } else {
+        package.strip_prefix(base_package).and_then(|nested| nested.strip_prefix('.'))
+    };
+    let Some(nested) = nested else {
+        return content.to_string();
+    };
+    let segments: Vec<&str> = nested.split('.').collect();
+
+    let mut output = String::new();
+    if !base_package.is_empty() {
+        writeln!(&mut output, "package {}", base_package).unwrap();
+        writeln!(&mut output).unwrap();
+    }
+    for (depth, segment) in segments.iter().enumerate() {
+        writeln!(&mut output, "{}package {} {{", "  ".repeat(depth), segment).unwrap();
+    }
+    let indent = "  ".repeat(segments.len());
+    for line in rest.lines() {
+        if line.is_empty() {
+            writeln!(&mut output).unwrap();
+        } else {
+            writeln!(&mut output, "{}{}", indent, line).unwrap();
+        }
+    }
+    for depth in (0..segments.len()).rev() {
+        writeln!(&mut output, "{}}}", "  ".repeat(depth)).unwrap();
+    }
+    output
+}
+
+/// Wrap the parameters of `def` signatures longer than `max_line_length`
+/// one per line, indented one level deeper than the `def`.
+///
+/// Signatures without parameters are left as they are.
+pub fn wrap_long_signatures(content: &str, max_line_length: usize) -> String {
+    let mut output = String::new();
+    for line in content.lines() {
+        match wrap_signature(line) {
+            Some(wrapped) if line.chars().count() > max_line_length => output.push_str(&wrapped),
+            _ => {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+    }
+    output
+}
+
+/// Wrap the first parameter list of a `def` line, if it has parameters.
+fn wrap_signature(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    if !trimmed.starts_with("def ") {
+        return None;
+    }
+    let indent = &line[..line.len() - trimmed.len()];
+    let open = parameter_list_start(line, indent.len() + "def ".len())?;
+
+    // Split the parameter list at top-level commas, outside of type arguments
+    let mut params = Vec::new();
+    let mut depth = 0;
+    let mut start = open + 1;
+    let mut close = None;
+    for (i, c) in line.char_indices().skip_while(|(i, _)| *i <= open) {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' if depth == 0 => {
+                params.push(line[start..i].trim());
+                close = Some(i);
+                break;
+            }
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(line[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let close = close?;
+    if params.iter().all(|param| param.is_empty()) {
+        return None;
+    }
+
+    let mut output = String::new();
+    writeln!(&mut output, "{}", &line[..=open]).unwrap();
+    for (i, param) in params.iter().enumerate() {
+        let separator = if i + 1 < params.len() { "," } else { "" };
+        writeln!(&mut output, "{}  {}{}", indent, param, separator).unwrap();
+    }
+    writeln!(&mut output, "{}{}", indent, &line[close..]).unwrap();
+    Some(output)
+}
+
+/// Find the `(` opening the parameter list of the method whose name starts
+/// at `name_start`, right after the name and any type parameters. Methods
+/// without parameter lists have none, even if their body contains a call.
+fn parameter_list_start(line: &str, name_start: usize) -> Option<usize> {
+    let rest = &line[name_start..];
+    let name_len = match rest.strip_prefix('`') {
+        Some(quoted) => quoted.find('`')? + 2,
+        None => rest.find(['(', '[', ':', '=', ' '])?,
+    };
+    let mut i = name_start + name_len;
+    if line[i..].starts_with('[') {
+        let mut depth = 0;
+        let end = line[i..].char_indices().find(|(_, c)| {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        })?;
+        i += end.0 + 1;
+    }
+    line[i..].starts_with('(').then_some(i)
+}
+
+/// Normalize generated Scala source before it is written out.
+///
+/// Trailing whitespace is trimmed from every line, and runs of three or more
+/// consecutive blank lines left behind by section separators are collapsed
+/// into a single blank line.
+pub fn normalize_output(content: &str) -> String {
+    fn push_blank_lines(output: &mut String, count: usize) {
+        let count = if count >= 3 { 1 } else { count };
+        for _ in 0..count {
+            output.push('\n');
+        }
+    }
+
+    let mut output = String::new();
+    let mut blank_lines = 0;
+
+    for line in content.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        push_blank_lines(&mut output, blank_lines);
+        blank_lines = 0;
+        output.push_str(line);
+        output.push('\n');
+    }
+    push_blank_lines(&mut output, blank_lines);
+
+    output
+}
+
 /// Context for Scala code generation, containing shared utilities and state.
 pub struct ScalaContext {
     opts: Opts,
     keywords: ScalaKeywords,
     /// Current interface being rendered (for cross-interface type references)
     current_interface: Option<InterfaceId>,
+    /// Whether the current interface is rendered as an export
+    exporting: bool,
+    /// Interfaces exported by the world; other exports reference their
+    /// exported types rather than the imported ones
+    exported_interfaces: HashSet<InterfaceId>,
+    /// Rendered type references for the current interface, which only depend
+    /// on the type and the interface it's referenced from
+    type_cache: HashMap<TypeId, String>,
+    /// Nesting depth of the type reference being rendered
+    type_depth: usize,
+    /// First error hit while rendering types, reported once the file is done
+    render_error: Option<String>,
+    /// Events recorded with the `debug` option
+    debug_events: Vec<DebugEvent>,
+    /// Constructs the runtime can't represent, collected with the `strict` option
+    unsupported: Vec<String>,
+    /// Item being rendered, used to locate unsupported constructs
+    location: Option<String>,
 }
 
 impl ScalaContext {
@@ -63,46 +419,243 @@ impl ScalaContext {
             opts: opts.clone(),
             keywords: ScalaKeywords::new(),
             current_interface: None,
+            exporting: false,
+            exported_interfaces: HashSet::new(),
+            type_cache: HashMap::new(),
+            type_depth: 0,
+            render_error: None,
+            debug_events: Vec::new(),
+            unsupported: Vec::new(),
+            location: None,
+        }
+    }
+
+    /// Take the first error hit while rendering, if any.
+    ///
+    /// Type rendering is infallible so that it composes with string building;
+    /// invalid types render as `Nothing` and record an error here instead.
+    pub fn take_render_error(&mut self) -> Option<String> {
+        self.render_error.take()
+    }
+
+    /// Set the interfaces exported by the world being generated.
+    pub fn set_exported_interfaces(&mut self, interface_ids: HashSet<InterfaceId>) {
+        self.type_cache.clear();
+        self.exported_interfaces = interface_ids;
+    }
+
+    /// Set whether the current interface is rendered as an export.
+    pub fn set_exporting(&mut self, exporting: bool) {
+        if self.exporting != exporting {
+            self.type_cache.clear();
+        }
+        self.exporting = exporting;
+    }
+
+    /// Record a debug event, if the `debug` option is enabled. Repeated
+    /// events are only recorded once.
+    pub fn record_debug(&mut self, event: DebugEvent) {
+        if self.opts.debug && !self.debug_events.contains(&event) {
+            self.debug_events.push(event);
+        }
+    }
+
+    /// Take the debug events recorded so far.
+    pub fn take_debug_events(&mut self) -> Vec<DebugEvent> {
+        std::mem::take(&mut self.debug_events)
+    }
+
+    /// Add debug events recorded by another generator, e.g. for another world.
+    pub fn extend_debug_events(&mut self, events: Vec<DebugEvent>) {
+        for event in events {
+            self.record_debug(event);
+        }
+    }
+
+    /// Record a construct the runtime can't represent, if the `strict` option
+    /// is enabled. It is located by the item currently being rendered.
+    fn report_unsupported(&mut self, problem: &str) {
+        if !self.opts.strict {
+            return;
+        }
+        let problem = match &self.location {
+            Some(location) => format!("{}: {}", location, problem),
+            None => problem.to_string(),
+        };
+        if !self.unsupported.contains(&problem) {
+            self.unsupported.push(problem);
         }
     }
 
+    /// Take the unsupported constructs collected so far.
+    pub fn take_unsupported(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.unsupported)
+    }
+
+    /// Set the item being rendered, such as ``function `read` ``, returning
+    /// the previous one so that it can be restored.
+    pub fn set_location(&mut self, location: Option<String>) -> Option<String> {
+        std::mem::replace(&mut self.location, location)
+    }
+
     /// Set the current interface being rendered (for cross-interface type references).
     pub fn set_current_interface(&mut self, interface_id: Option<InterfaceId>) {
+        if self.current_interface != interface_id {
+            self.type_cache.clear();
+        }
         self.current_interface = interface_id;
     }
 
     /// Generate fully qualified package path for a type from another interface.
     fn get_qualified_type_name(&self, resolve: &Resolve, type_id: TypeId, type_name: &str) -> String {
         let ty = &resolve.types[type_id];
+        let scala_name = if matches!(ty.kind, TypeDefKind::Resource) {
+            self.resource_name(type_name)
+        } else {
+            self.to_pascal_case(type_name)
+        };
 
-        // Check if this type is from a different interface
         if let TypeOwner::Interface(type_interface_id) = ty.owner {
-            // If we're in an interface and the type is from a different interface, qualify it
-            if let Some(current_interface_id) = self.current_interface {
-                if type_interface_id != current_interface_id {
-                    // Type is from a different interface - need fully qualified name
-                    let type_interface = &resolve.interfaces[type_interface_id];
-                    let interface_name = type_interface.name.as_ref().expect("Interface must have a name");
-
-                    if let Some(package_id) = type_interface.package {
-                        let package = &resolve.packages[package_id];
-                        let pkg_name = &package.name;
-
-                        // Build the fully qualified path
-                        let mut segments = self.base_package_segments();
-                        segments.push(self.to_snake_case(&pkg_name.namespace));
-                        segments.push(self.to_snake_case(&pkg_name.name));
-                        segments.push(self.to_snake_case(interface_name));
-                        segments.push(self.to_pascal_case(type_name));
-
-                        return segments.join(".");
+            let type_interface = &resolve.interfaces[type_interface_id];
+
+            if let Some(package_id) = type_interface.package {
+                let package = &resolve.packages[package_id];
+                let pkg_name = &package.name;
+
+                // Types collected into the package's shared `types` object are
+                // always referenced there
+                if self.is_shared_type(resolve, type_id) {
+                    let mut segments = self.base_package_segments();
+                    segments.push(self.to_snake_case(&pkg_name.namespace));
+                    segments.push(self.to_snake_case(&pkg_name.name));
+                    segments.push(self.shared_types_object().to_string());
+                    segments.push(scala_name);
+                    return segments.join(".");
+                }
+
+                // Type is from a different interface (or we're outside of any
+                // interface) - need fully qualified name
+                if self.current_interface != Some(type_interface_id) {
+                    // Build the fully qualified path. Exports referencing another
+                    // exported interface use its types from the companion object
+                    // of its export trait (or its package for resource files, or
+                    // its package object for type-only interfaces).
+                    let exported = self.exporting && self.exported_interfaces.contains(&type_interface_id);
+                    let in_companion = exported
+                        && !interface::is_type_only(resolve, type_interface)
+                        && !(self.opts.module_per_resource && matches!(ty.kind, TypeDefKind::Resource));
+                    let mut segments = self.base_package_segments();
+                    if exported {
+                        segments.push(self.exports_segment().to_string());
                     }
+                    segments.push(self.to_snake_case(&pkg_name.namespace));
+                    segments.push(self.to_snake_case(&pkg_name.name));
+                    if in_companion {
+                        segments.push(
+                            self.to_pascal_case(type_interface.name.as_ref().expect("Interface must have a name")),
+                        );
+                    } else {
+                        segments.push(self.package_object_name(resolve, type_interface_id));
+                    }
+                    segments.push(scala_name);
+
+                    return segments.join(".");
                 }
             }
         }
 
-        // Same interface or no interface context - use simple name
-        self.to_pascal_case(type_name)
+        // World-level types live in the world's import package object, so they
+        // are qualified when referenced from outside of it (e.g. world exports)
+        if let TypeOwner::World(world_id) = ty.owner {
+            if self.current_interface.is_none() {
+                let world_name = self.world_name(&resolve.worlds[world_id].name);
+                return format!("{}.{}", world::get_world_package_path(self, world_name, true), scala_name);
+            }
+        }
+
+        // Same interface or not owned by an interface - use simple name
+        scala_name
+    }
+
+    /// Get the name of the `package object` generated for an interface, as
+    /// chosen by the `package_object_name` option.
+    pub fn package_object_name(&self, resolve: &Resolve, interface_id: InterfaceId) -> String {
+        let interface = &resolve.interfaces[interface_id];
+        match &self.opts.package_object_name {
+            PackageObjectName::Interface => {
+                self.to_snake_case(interface.name.as_ref().expect("Interface must have a name"))
+            }
+            PackageObjectName::LastPackageSegment => match interface.package {
+                Some(package_id) => self.to_snake_case(&resolve.packages[package_id].name.name),
+                None => self.to_snake_case(interface.name.as_ref().expect("Interface must have a name")),
+            },
+            PackageObjectName::Fixed(name) => name.clone(),
+        }
+    }
+
+    /// Get the existing Scala type a WIT type is mapped to with the `type_map`
+    /// option, if any.
+    ///
+    /// Types are identified by their interface and name, with or without the
+    /// package version, e.g. `wasi:clocks/wall-clock.datetime`.
+    pub fn mapped_type(&self, resolve: &Resolve, type_id: TypeId) -> Option<&str> {
+        if self.opts.type_map.is_empty() {
+            return None;
+        }
+        let ty = &resolve.types[type_id];
+        let (TypeOwner::Interface(interface_id), Some(type_name)) = (ty.owner, &ty.name) else {
+            return None;
+        };
+        let interface = &resolve.interfaces[interface_id];
+        let (Some(package_id), Some(interface_name)) = (interface.package, &interface.name) else {
+            return None;
+        };
+        let package = &resolve.packages[package_id].name;
+        let unversioned = format!("{}:{}/{}.{}", package.namespace, package.name, interface_name, type_name);
+        let versioned = package
+            .version
+            .as_ref()
+            .map(|version| format!("{}:{}/{}@{}.{}", package.namespace, package.name, interface_name, version, type_name));
+        self.opts
+            .type_map
+            .iter()
+            .filter_map(|mapping| mapping.split_once('='))
+            .find(|(wit_type, _)| *wit_type == unversioned || Some(*wit_type) == versioned.as_deref())
+            .map(|(_, scala_type)| scala_type)
+    }
+
+    /// Check whether a type, or a type it aliases, is mapped with `type_map`.
+    fn is_mapped_alias(&self, resolve: &Resolve, mut type_id: TypeId) -> bool {
+        loop {
+            if self.mapped_type(resolve, type_id).is_some() {
+                return true;
+            }
+            match resolve.types[type_id].kind {
+                TypeDefKind::Type(Type::Id(aliased)) => type_id = aliased,
+                _ => return false,
+            }
+        }
+    }
+
+    /// Check whether a type definition is emitted into its package's shared
+    /// `types` object rather than into its interface.
+    ///
+    /// With `shared_types` enabled this covers records, variants, enums and
+    /// flags defined in interfaces that belong to a package.
+    pub fn is_shared_type(&self, resolve: &Resolve, type_id: TypeId) -> bool {
+        if !self.opts.shared_types || self.mapped_type(resolve, type_id).is_some() {
+            return false;
+        }
+        let ty = &resolve.types[type_id];
+        let in_package = match ty.owner {
+            TypeOwner::Interface(interface_id) => resolve.interfaces[interface_id].package.is_some(),
+            _ => false,
+        };
+        in_package
+            && matches!(
+                ty.kind,
+                TypeDefKind::Record(_) | TypeDefKind::Variant(_) | TypeDefKind::Enum(_) | TypeDefKind::Flags(_)
+            )
     }
 
     /// Render a WIT type to its Scala equivalent with fully qualified names.
@@ -120,41 +673,75 @@ impl ScalaContext {
             | Type::U64
             | Type::F32
             | Type::F64
-            | Type::Char
             | Type::String => self.render_primitive_type(ty).to_string(),
+            Type::Char => {
+                self.report_unsupported("`char` values outside the Basic Multilingual Plane are truncated to a UTF-16 `Char`");
+                self.render_primitive_type(ty).to_string()
+            }
             Type::Id(id) => self.render_type_id(resolve, *id),
+            Type::ErrorContext if self.opts.strict => {
+                self.report_unsupported("`error-context` is not supported by the runtime");
+                "Nothing".to_string()
+            }
             Type::ErrorContext => panic!("ErrorContext type is not supported"),
         }
     }
 
     /// Render a type ID reference with fully qualified name.
+    ///
+    /// Results are cached until the current interface changes.
     fn render_type_id(&mut self, resolve: &Resolve, id: TypeId) -> String {
+        // Strict mode renders every reference so that unsupported constructs
+        // are reported at each place they're used
+        if let Some(rendered) = self.type_cache.get(&id).filter(|_| !self.opts.strict) {
+            return rendered.clone();
+        }
+        // Guard against pathologically nested types overflowing the stack
+        let max_depth = self.max_type_depth();
+        if self.type_depth >= max_depth {
+            self.render_error.get_or_insert_with(|| {
+                format!(
+                    "type nesting exceeds the maximum depth of {} (see `--max-type-depth`)",
+                    max_depth
+                )
+            });
+            return "Nothing".to_string();
+        }
+        self.type_depth += 1;
+        let rendered = self.render_type_id_uncached(resolve, id);
+        self.type_depth -= 1;
+        if let Some(type_name) = &resolve.types[id].name {
+            if rendered.contains('.') && !rendered.contains('[') {
+                self.record_debug(DebugEvent::QualifiedType {
+                    type_name: type_name.clone(),
+                    qualified: rendered.clone(),
+                });
+            }
+        }
+        self.type_cache.insert(id, rendered.clone());
+        rendered
+    }
+
+    fn render_type_id_uncached(&mut self, resolve: &Resolve, id: TypeId) -> String {
         let ty = &resolve.types[id];
+        if let Some(mapped) = self.mapped_type(resolve, id) {
+            return mapped.to_string();
+        }
 
         // Check what kind of type this is
         match &ty.kind {
-            TypeDefKind::List(inner) => {
-                // list<T> maps to Array[T]
-                format!("Array[{}]", self.render_type(resolve, inner))
-            }
+            TypeDefKind::List(inner) => self.render_list(resolve, inner),
             TypeDefKind::Option(inner) => {
                 // option<T> maps to java.util.Optional[T]
                 format!("java.util.Optional[{}]", self.render_type(resolve, inner))
             }
-            TypeDefKind::Result(result) => {
-                // result<T, E> maps to scala.scalajs.wit.Result[T, E]
-                let ok_type = result
-                    .ok
-                    .as_ref()
-                    .map(|t| self.render_type(resolve, t))
-                    .unwrap_or_else(|| "Unit".to_string());
-                let err_type = result
-                    .err
-                    .as_ref()
-                    .map(|t| self.render_type(resolve, t))
-                    .unwrap_or_else(|| "Unit".to_string());
-                format!("scala.scalajs.wit.Result[{}, {}]", ok_type, err_type)
-            }
+            TypeDefKind::Result(result) => match &ty.name {
+                // Named results rendered as variants are referenced by name
+                Some(type_name) if self.opts.named_result_as_variant => {
+                    self.get_qualified_type_name(resolve, id, type_name)
+                }
+                _ => self.render_result(resolve, result),
+            },
             TypeDefKind::Tuple(tuple) => {
                 // tuple<T1, T2, ...> maps to scala.scalajs.wit.TupleN[...]
                 let type_params: Vec<String> = tuple
@@ -162,6 +749,7 @@ impl ScalaContext {
                     .iter()
                     .map(|t| self.render_type(resolve, t))
                     .collect();
+                self.check_tuple_arity(tuple);
                 format!(
                     "scala.scalajs.wit.Tuple{}[{}]",
                     type_params.len(),
@@ -173,19 +761,36 @@ impl ScalaContext {
             | TypeDefKind::Enum(_)
             | TypeDefKind::Flags(_) => {
                 // Named types - use qualified name if from different interface
-                let type_name = ty.name.as_ref().expect("Named types must have a name");
-                self.get_qualified_type_name(resolve, id, type_name)
+                match &ty.name {
+                    Some(type_name) => self.get_qualified_type_name(resolve, id, type_name),
+                    None => {
+                        let type_name = self.anonymous_type_name(resolve, id);
+                        self.get_qualified_type_name(resolve, id, &type_name)
+                    }
+                }
             }
             TypeDefKind::Type(inner) => {
-                // Type alias - render the underlying type
-                self.render_type(resolve, inner)
+                // Named aliases of primitive or anonymous types defined by an
+                // interface are referenced by their alias name. Aliases of named
+                // types (including `use`s) render the type they refer to.
+                let aliases_named_type = matches!(inner, Type::Id(inner_id) if resolve.types[*inner_id].name.is_some());
+                match &ty.name {
+                    Some(type_name) if matches!(ty.owner, TypeOwner::Interface(_)) && !aliases_named_type => {
+                        self.get_qualified_type_name(resolve, id, type_name)
+                    }
+                    _ => self.render_type(resolve, inner),
+                }
             }
             TypeDefKind::Handle(handle) => {
-                // Handle to a resource - follow the reference to get the resource name
+                // Handle to a resource - follow the reference to get the resource name.
+                // Both `own<T>` and `borrow<T>` are represented by the resource trait.
                 use wit_bindgen_core::wit_parser::Handle;
                 let resource_id = match handle {
                     Handle::Own(id) | Handle::Borrow(id) => *id,
                 };
+                // The handle may point at a `use`d alias of the resource; qualify
+                // it by the interface that actually defines the resource.
+                let resource_id = dealias(resolve, resource_id);
                 let resource_ty = &resolve.types[resource_id];
                 let type_name = resource_ty
                     .name
@@ -203,6 +808,7 @@ impl ScalaContext {
                 format!("Array[{}]", self.render_type(resolve, inner))
             }
             TypeDefKind::Future(_) | TypeDefKind::Stream(_) | TypeDefKind::Unknown => {
+                self.report_unsupported_kind(&ty.kind);
                 "Unknown".to_string()
             }
         }
@@ -226,24 +832,80 @@ impl ScalaContext {
             Type::F32 => "Float",
             Type::F64 => "Double",
             Type::Char => "Char",
-            Type::String => "String",
+            Type::String => match self.opts.string_type {
+                StringType::String => "String",
+                StringType::WitString => "scala.scalajs.wit.WitString",
+            },
             _ => unreachable!("Not a primitive type: {:?}", ty),
         }
     }
 
+    /// Get the WIT-style name synthesized for an anonymous record, variant,
+    /// enum or flags type, e.g. `anonymous-record-0c1f2e3d`.
+    ///
+    /// The name is derived from the type's structure, so it stays the same
+    /// across runs and identical anonymous types share one definition.
+    pub fn anonymous_type_name(&mut self, resolve: &Resolve, id: TypeId) -> String {
+        let (kind, parts): (&str, Vec<String>) = match &resolve.types[id].kind {
+            TypeDefKind::Record(record) => (
+                "record",
+                record
+                    .fields
+                    .iter()
+                    .map(|field| format!("{}: {}", field.name, self.render_type(resolve, &field.ty)))
+                    .collect(),
+            ),
+            TypeDefKind::Variant(variant) => (
+                "variant",
+                variant
+                    .cases
+                    .iter()
+                    .map(|case| match &case.ty {
+                        Some(ty) => format!("{}({})", case.name, self.render_type(resolve, ty)),
+                        None => case.name.clone(),
+                    })
+                    .collect(),
+            ),
+            TypeDefKind::Enum(enum_) => ("enum", enum_.cases.iter().map(|case| case.name.clone()).collect()),
+            TypeDefKind::Flags(flags) => ("flags", flags.flags.iter().map(|flag| flag.name.clone()).collect()),
+            kind => unreachable!("Not an aggregate type: {:?}", kind),
+        };
+        format!("anonymous-{}-{:08x}", kind, structural_hash(kind, &parts) as u32)
+    }
+
     /// Render a typedef (record, variant, enum, flags, etc.) to Scala code.
+    ///
+    /// Anonymous aggregate types are rendered under their synthesized name.
+    ///
+    /// Type references are qualified relative to the interface set with
+    /// [`ScalaContext::set_current_interface`]: types of that interface use
+    /// their simple name, so the current interface must be the one the
+    /// typedef is rendered into. Use [`ScalaContext::render_typedef_in`] to
+    /// render a typedef standalone.
     pub fn render_typedef(&mut self, resolve: &Resolve, id: TypeId) -> String {
         let ty = &resolve.types[id];
-        let name = ty.name.as_ref().expect("Type must have a name");
-        let type_name = self.to_pascal_case(name);
+        let name = match &ty.name {
+            Some(name) => name.clone(),
+            None => self.anonymous_type_name(resolve, id),
+        };
+        let type_name = self.to_pascal_case(&name);
+        // Mapped types reference an existing Scala type instead of a definition
+        if let Some(mapped) = self.mapped_type(resolve, id) {
+            return format!("// Mapped: {} = {}", type_name, mapped);
+        }
+        let docs = format_docs_with_stability(&ty.docs, &ty.stability, 0);
+        let previous_location = self.set_location(Some(format!("type `{}`", name)));
 
-        match &ty.kind {
-            TypeDefKind::Record(record) => self.render_record(&type_name, record, resolve, &ty.docs),
-            TypeDefKind::Variant(variant) => self.render_variant(&type_name, variant, resolve, &ty.docs),
-            TypeDefKind::Enum(enum_) => self.render_enum(&type_name, enum_, &ty.docs),
-            TypeDefKind::Flags(flags) => self.render_flags(&type_name, flags, &ty.docs),
+        let typedef = match &ty.kind {
+            TypeDefKind::Record(record) => self.render_record(&type_name, &name, record, resolve, &docs),
+            TypeDefKind::Variant(variant) => self.render_variant(&type_name, &name, variant, resolve, &docs),
+            TypeDefKind::Enum(enum_) => self.render_enum(&type_name, &name, enum_, &docs),
+            TypeDefKind::Flags(flags) => self.render_flags(&type_name, flags, &docs),
             TypeDefKind::Tuple(tuple) => self.render_tuple_typedef(&type_name, tuple, resolve),
             TypeDefKind::Option(inner) => self.render_option_typedef(&type_name, inner, resolve),
+            TypeDefKind::Result(result) if self.opts.named_result_as_variant && ty.name.is_some() => {
+                self.render_result_variant(&type_name, &name, result, resolve, &docs)
+            }
             TypeDefKind::Result(result) => self.render_result_typedef(&type_name, result, resolve),
             TypeDefKind::List(inner) => self.render_list_typedef(&type_name, inner, resolve),
             TypeDefKind::Type(inner) => {
@@ -267,54 +929,405 @@ impl ScalaContext {
                     size
                 )
             }
+            TypeDefKind::Future(_) | TypeDefKind::Stream(_) | TypeDefKind::Unknown if self.opts.strict => {
+                self.report_unsupported_kind(&ty.kind);
+                format!("type {} = Unknown", type_name)
+            }
             TypeDefKind::Future(_) | TypeDefKind::Stream(_) | TypeDefKind::Unknown => {
                 panic!("Unsupported type: {:?}", ty.kind)
             }
+        };
+        self.set_location(previous_location);
+
+        // Annotations added with `type_annotation` go between the docs and
+        // the definition, above the WIT annotations
+        let annotations: Vec<&str> = self
+            .opts
+            .type_annotation
+            .iter()
+            .filter_map(|annotation| annotation.split_once('='))
+            .filter(|(wit_name, _)| *wit_name == name)
+            .map(|(_, annotation)| annotation)
+            .collect();
+        if annotations.is_empty() || typedef.starts_with("//") {
+            return typedef;
+        }
+        let definition = typedef.strip_prefix(docs.as_str()).unwrap_or(&typedef);
+        format!("{}{}\n{}", docs, annotations.join("\n"), definition)
+    }
+
+    /// Report a `future`, `stream` or unknown type, none of which the runtime supports.
+    fn report_unsupported_kind(&mut self, kind: &TypeDefKind) {
+        let kind = match kind {
+            TypeDefKind::Future(_) => "future",
+            TypeDefKind::Stream(_) => "stream",
+            _ => "unknown",
+        };
+        self.report_unsupported(&format!("`{}` types are not supported by the runtime", kind));
+    }
+
+    /// Report tuples longer than the runtime's largest tuple, `Tuple22`.
+    fn check_tuple_arity(&mut self, tuple: &Tuple) {
+        if tuple.types.len() > stub::MAX_TUPLE_ARITY {
+            self.report_unsupported(&format!(
+                "tuple of {} elements exceeds the {} elements of `scala.scalajs.wit.Tuple{}`",
+                tuple.types.len(),
+                stub::MAX_TUPLE_ARITY,
+                stub::MAX_TUPLE_ARITY
+            ));
         }
     }
 
+    /// Render a typedef as it appears in the given interface, restoring the
+    /// current interface afterwards.
+    pub fn render_typedef_in(&mut self, resolve: &Resolve, id: TypeId, interface_id: InterfaceId) -> String {
+        let previous = self.current_interface;
+        self.set_current_interface(Some(interface_id));
+        let typedef = self.render_typedef(resolve, id);
+        self.set_current_interface(previous);
+        typedef
+    }
+
     /// Render a record type as a Scala case class.
-    fn render_record(&mut self, name: &str, record: &Record, resolve: &Resolve, type_docs: &Docs) -> String {
+    fn render_record(
+        &mut self,
+        name: &str,
+        wit_name: &str,
+        record: &Record,
+        resolve: &Resolve,
+        docs: &str,
+    ) -> String {
         let mut output = String::new();
 
         // Generate scaladoc if docs exist
-        let docs = format_docs(type_docs);
         if !docs.is_empty() {
             write!(&mut output, "{}", docs).unwrap();
         }
 
+        let fields: Vec<String> = record
+            .fields
+            .iter()
+            .map(|field| {
+                let field_name = self.field_name(wit_name, &field.name);
+                let field_type = self.render_type(resolve, &field.ty);
+                format!("{}: {}", field_name, field_type)
+            })
+            .collect();
+
         writeln!(&mut output, "{}", annotations::component_record()).unwrap();
+        if let Some(uid) = &self.opts.serial_version_uid {
+            let uid = match uid {
+                SerialVersionUid::Fixed(uid) => *uid,
+                SerialVersionUid::Auto => structural_hash(wit_name, &fields),
+            };
+            writeln!(&mut output, "@SerialVersionUID({}L)", uid).unwrap();
+        }
         write!(&mut output, "final case class {}(", name).unwrap();
 
-        for (i, field) in record.fields.iter().enumerate() {
-            if i > 0 {
-                write!(&mut output, ", ").unwrap();
+        if self.opts.multiline_records && !fields.is_empty() {
+            // One field per line, in WIT declaration order; only Scala 3 gets
+            // a trailing comma
+            writeln!(&mut output).unwrap();
+            for (i, field) in fields.iter().enumerate() {
+                let separator = if i + 1 < fields.len() || self.opts.scala_version == ScalaVersion::Scala3 {
+                    ","
+                } else {
+                    ""
+                };
+                writeln!(&mut output, "  {}{}", field, separator).unwrap();
+            }
+        } else {
+            write!(&mut output, "{}", fields.join(", ")).unwrap();
+        }
+
+        // Focused update helpers for fields holding a record or an option
+        let mut helpers = Vec::new();
+        if self.opts.record_update_helpers {
+            for field in &record.fields {
+                let nested = match field.ty {
+                    Type::Id(id) => matches!(
+                        resolve.types[dealias(resolve, id)].kind,
+                        TypeDefKind::Record(_) | TypeDefKind::Option(_)
+                    ),
+                    _ => false,
+                };
+                if nested {
+                    let field_name = self.field_name(wit_name, &field.name);
+                    let field_type = self.render_type(resolve, &field.ty);
+                    helpers.push(format!(
+                        "def with{}(f: {} => {}): {} = copy({} = f({}))",
+                        match self.renamed(&format!("{}.{}", wit_name, field.name)) {
+                            Some(renamed) => capitalize(renamed.trim_matches('`')),
+                            None => self.to_pascal_case(&field.name),
+                        },
+                        field_type,
+                        field_type,
+                        name,
+                        field_name,
+                        field_name
+                    ));
+                }
             }
-            let field_name = self.to_camel_case(&field.name);
-            let field_type = self.render_type(resolve, &field.ty);
-            write!(&mut output, "{}: {}", field_name, field_type).unwrap();
         }
 
-        writeln!(&mut output, ")").unwrap();
+        write!(&mut output, ")").unwrap();
+        if self.opts.serial_version_uid.is_some() {
+            write!(&mut output, " extends Serializable").unwrap();
+        } else if self.opts.collapse_single_field_records && record.fields.len() == 1 {
+            write!(&mut output, " extends AnyVal").unwrap();
+        }
+        if helpers.is_empty() {
+            writeln!(&mut output).unwrap();
+        } else {
+            writeln!(&mut output, " {{").unwrap();
+            for helper in &helpers {
+                writeln!(&mut output, "  {}", helper).unwrap();
+            }
+            writeln!(&mut output, "}}").unwrap();
+        }
+
+        let show = if self.opts.emit_show {
+            let shown_fields: Vec<String> = record
+                .fields
+                .iter()
+                .map(|field| {
+                    let field_name = self.field_name(wit_name, &field.name);
+                    let shown = self.show_interpolation(resolve, &field.ty, &format!("value.{}", field_name), 0);
+                    format!("{} = {}", field_name.trim_matches('`'), shown)
+                })
+                .collect();
+            self.render_show_instance(name, &format!("s\"{}({})\"", name, shown_fields.join(", ")))
+        } else {
+            String::new()
+        };
+
+        if self.opts.emit_wit_names || self.opts.record_field_names || !show.is_empty() {
+            writeln!(&mut output, "object {} {{", name).unwrap();
+            if self.opts.emit_wit_names {
+                let fields: Vec<&str> = record.fields.iter().map(|f| f.name.as_str()).collect();
+                write!(
+                    &mut output,
+                    "{}",
+                    self.render_wit_names(wit_name, "witFieldNames", &fields, |f| self.field_name(wit_name, f))
+                )
+                .unwrap();
+            }
+            if self.opts.record_field_names {
+                // Scala and WIT names of the fields, in declaration order
+                let entries: Vec<String> = record
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        format!(
+                            "({}, {})",
+                            annotations::string_literal(self.field_name(wit_name, &field.name).trim_matches('`')),
+                            annotations::string_literal(&field.name)
+                        )
+                    })
+                    .collect();
+                writeln!(
+                    &mut output,
+                    "  val fieldNames: Array[(String, String)] = Array({})",
+                    entries.join(", ")
+                )
+                .unwrap();
+            }
+            write!(&mut output, "{}", show).unwrap();
+            writeln!(&mut output, "}}").unwrap();
+        }
+
+        output
+    }
+
+    /// Render a `Show` instance for a type, indented for its companion object,
+    /// if `emit_show` is enabled. `show_expr` renders the shown `value`.
+    fn render_show_instance(&self, name: &str, show_expr: &str) -> String {
+        if !self.opts.emit_show {
+            return String::new();
+        }
+        let show_type = self.show_typeclass();
+        let instance = instance_name(name, "Show");
+        let mut output = String::new();
+        match self.opts.scala_version {
+            ScalaVersion::Scala2 => writeln!(
+                &mut output,
+                "  implicit val {}: {}[{}] = new {}[{}] {{",
+                instance, show_type, name, show_type, name
+            )
+            .unwrap(),
+            ScalaVersion::Scala3 => {
+                writeln!(&mut output, "  given {}: {}[{}] with {{", instance, show_type, name).unwrap()
+            }
+        }
+        writeln!(&mut output, "    def show(value: {}): String = {}", name, show_expr).unwrap();
+        writeln!(&mut output, "  }}").unwrap();
         output
     }
 
+    /// Render an expression summoning the `Show` instance of a type.
+    fn summon_show(&self, type_name: &str) -> String {
+        let summon = match self.opts.scala_version {
+            ScalaVersion::Scala2 => "implicitly",
+            ScalaVersion::Scala3 => "summon",
+        };
+        format!("{}[{}[{}]]", summon, self.show_typeclass(), type_name)
+    }
+
+    /// Render an expression showing `value` of the given type, or `None` if its
+    /// `toString` already shows it.
+    ///
+    /// Generated types are shown with their own instances, while lists,
+    /// options, tuples and results show their elements recursively. Mapped
+    /// types are existing Scala types without a generated instance. `depth`
+    /// keeps the names of nested lambda parameters apart.
+    fn show_expr(&mut self, resolve: &Resolve, ty: &Type, value: &str, depth: usize) -> Option<String> {
+        let Type::Id(id) = *ty else {
+            return None;
+        };
+        if self.is_mapped_alias(resolve, id) {
+            return None;
+        }
+        let x = format!("x{}", depth);
+        let typedef = &resolve.types[dealias(resolve, id)];
+        match &typedef.kind {
+            TypeDefKind::Record(_) | TypeDefKind::Variant(_) | TypeDefKind::Enum(_) | TypeDefKind::Flags(_) => {
+                let type_name = self.render_type(resolve, ty);
+                Some(format!("{}.show({})", self.summon_show(&type_name), value))
+            }
+            TypeDefKind::Result(_) if typedef.name.is_some() && self.opts.named_result_as_variant => {
+                let type_name = self.render_type(resolve, ty);
+                Some(format!("{}.show({})", self.summon_show(&type_name), value))
+            }
+            TypeDefKind::List(inner) | TypeDefKind::FixedSizeList(inner, _) => {
+                // Lists rendered as something else than an array keep their `toString`
+                if !self.render_type(resolve, ty).starts_with("Array[") {
+                    return None;
+                }
+                let elements = match self.show_expr(resolve, inner, &x, depth + 1) {
+                    Some(element) => format!("{}.map({} => {})", value, x, element),
+                    None => value.to_string(),
+                };
+                Some(format!("{}.mkString(\"[\", \", \", \"]\")", elements))
+            }
+            TypeDefKind::Option(inner) => {
+                let payload = self.show_interpolation(resolve, inner, &format!("{}.get", value), depth);
+                Some(format!("(if ({}.isPresent) s\"Some({})\" else \"None\")", value, payload))
+            }
+            TypeDefKind::Tuple(tuple) => {
+                let elements: Vec<String> = tuple
+                    .types
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| self.show_interpolation(resolve, t, &format!("{}._{}", value, i + 1), depth))
+                    .collect();
+                Some(format!("s\"({})\"", elements.join(", ")))
+            }
+            TypeDefKind::Result(result) => {
+                let (ok, err) = match self.opts.result_type {
+                    ResultType::Runtime => ("Ok", "Err"),
+                    ResultType::Either => ("Right", "Left"),
+                };
+                // Each arm binds the payload, or ignores it if the arm has none
+                let mut arm = |case: &str, payload: &Option<Type>| match payload {
+                    Some(payload) => {
+                        let shown = self.show_interpolation(resolve, payload, &x, depth + 1);
+                        (x.clone(), format!("s\"{}({})\"", case, shown))
+                    }
+                    None => ("_".to_string(), format!("\"{}\"", case)),
+                };
+                let (ok_binder, ok_shown) = arm(ok, &result.ok);
+                let (err_binder, err_shown) = arm(err, &result.err);
+                Some(match self.opts.result_type {
+                    ResultType::Runtime => format!(
+                        "({} match {{ case scala.scalajs.wit.Ok({}) => {} case scala.scalajs.wit.Err({}) => {} }})",
+                        value, ok_binder, ok_shown, err_binder, err_shown
+                    ),
+                    ResultType::Either => format!(
+                        "{}.fold({} => {}, {} => {})",
+                        value, err_binder, err_shown, ok_binder, ok_shown
+                    ),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Render an interpolated `${...}` segment showing `value` of the given type.
+    fn show_interpolation(&mut self, resolve: &Resolve, ty: &Type, value: &str, depth: usize) -> String {
+        let shown = self.show_expr(resolve, ty, value, depth).unwrap_or_else(|| value.to_string());
+        format!("${{{}}}", shown)
+    }
+
+    /// Get the fully qualified `Show` typeclass used by `emit_show`.
+    fn show_typeclass(&self) -> &str {
+        self.opts.show_typeclass.as_deref().unwrap_or("cats.Show")
+    }
+
     /// Render a variant type as a Scala sealed trait with case classes.
-    fn render_variant(&mut self, name: &str, variant: &Variant, resolve: &Resolve, type_docs: &Docs) -> String {
+    fn render_variant(
+        &mut self,
+        name: &str,
+        wit_name: &str,
+        variant: &Variant,
+        resolve: &Resolve,
+        docs: &str,
+    ) -> String {
         let mut output = String::new();
 
         // Generate scaladoc if docs exist
-        let docs = format_docs(type_docs);
         if !docs.is_empty() {
             write!(&mut output, "{}", docs).unwrap();
         }
 
         writeln!(&mut output, "{}", annotations::component_variant()).unwrap();
+
+        // A variant with a single payload case acts as a newtype wrapper
+        if let [Case { ty: Some(ty), .. }] = variant.cases.as_slice() {
+            if self.opts.flatten_single_variant {
+                let payload_type = self.render_type(resolve, ty);
+                writeln!(&mut output, "final case class {}(value: {})", name, payload_type).unwrap();
+                let shown = self.show_interpolation(resolve, ty, "value.value", 0);
+                let show = self.render_show_instance(name, &format!("s\"{}({})\"", name, shown));
+                if self.opts.emit_wit_names || !show.is_empty() {
+                    writeln!(&mut output, "object {} {{", name).unwrap();
+                    if self.opts.emit_wit_names {
+                        let cases: Vec<&str> = variant.cases.iter().map(|c| c.name.as_str()).collect();
+                        write!(
+                            &mut output,
+                            "{}",
+                            self.render_wit_names(wit_name, "witCaseNames", &cases, |c| self.to_pascal_case(c))
+                        )
+                        .unwrap();
+                    }
+                    write!(&mut output, "{}", show).unwrap();
+                    writeln!(&mut output, "}}").unwrap();
+                }
+                return output;
+            }
+        }
+
         writeln!(&mut output, "sealed trait {}", name).unwrap();
         writeln!(&mut output, "object {} {{", name).unwrap();
 
         for case in &variant.cases {
-            let case_name = self.to_pascal_case(&case.name);
+            let case_name = self.case_name(name, wit_name, &case.name);
+            if let Some(tuple) = self.destructured_tuple_payload(resolve, case.ty.as_ref()) {
+                let fields: Vec<String> = tuple
+                    .types
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| format!("_{}: {}", i, self.render_type(resolve, t)))
+                    .collect();
+                writeln!(
+                    &mut output,
+                    "  final case class {}({}) extends {}",
+                    case_name,
+                    fields.join(", "),
+                    name
+                )
+                .unwrap();
+                continue;
+            }
             match &case.ty {
                 Some(ty) => {
                     let case_type = self.render_type(resolve, ty);
@@ -331,16 +1344,82 @@ impl ScalaContext {
             }
         }
 
+        if self.opts.emit_wit_names {
+            let cases: Vec<&str> = variant.cases.iter().map(|c| c.name.as_str()).collect();
+            write!(
+                &mut output,
+                "{}",
+                self.render_wit_names(wit_name, "witCaseNames", &cases, |c| self.case_name(name, wit_name, c))
+            )
+            .unwrap();
+        }
+
+        if self.opts.emit_show {
+            // Each case is shown with its payload, like a record
+            let arms: Vec<String> = variant
+                .cases
+                .iter()
+                .map(|case| {
+                    let case_name = self.case_name(name, wit_name, &case.name);
+                    let shown_name = case_name.trim_matches('`').to_string();
+                    if let Some(tuple) = self.destructured_tuple_payload(resolve, case.ty.as_ref()) {
+                        let payloads: Vec<String> = (0..tuple.types.len()).map(|i| format!("payload{}", i)).collect();
+                        let shown: Vec<String> = tuple
+                            .types
+                            .iter()
+                            .zip(&payloads)
+                            .map(|(t, payload)| self.show_interpolation(resolve, t, payload, 0))
+                            .collect();
+                        return format!(
+                            "case {}.{}({}) => s\"{}({})\"",
+                            name,
+                            case_name,
+                            payloads.join(", "),
+                            shown_name,
+                            shown.join(", ")
+                        );
+                    }
+                    match &case.ty {
+                        Some(ty) => format!(
+                            "case {}.{}(payload) => s\"{}({})\"",
+                            name,
+                            case_name,
+                            shown_name,
+                            self.show_interpolation(resolve, ty, "payload", 0)
+                        ),
+                        None => format!("case {}.{} => \"{}\"", name, case_name, shown_name),
+                    }
+                })
+                .collect();
+            let arms: String = arms.iter().map(|arm| format!("      {}\n", arm)).collect();
+            let show_expr = format!("value match {{\n{}    }}", arms);
+            write!(&mut output, "{}", self.render_show_instance(name, &show_expr)).unwrap();
+        }
+
         writeln!(&mut output, "}}").unwrap();
         output
     }
 
+    /// Get the tuple to destructure into separate fields for a variant case payload,
+    /// if `destructure_tuple_payloads` is enabled and the payload is a tuple.
+    fn destructured_tuple_payload<'a>(&self, resolve: &'a Resolve, ty: Option<&Type>) -> Option<&'a Tuple> {
+        if !self.opts.destructure_tuple_payloads {
+            return None;
+        }
+        match ty {
+            Some(Type::Id(id)) => match &resolve.types[dealias(resolve, *id)].kind {
+                TypeDefKind::Tuple(tuple) => Some(tuple),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     /// Render an enum type as a Scala sealed trait with case objects.
-    fn render_enum(&mut self, name: &str, enum_: &Enum, type_docs: &Docs) -> String {
+    fn render_enum(&mut self, name: &str, wit_name: &str, enum_: &Enum, docs: &str) -> String {
         let mut output = String::new();
 
         // Generate scaladoc if docs exist
-        let docs = format_docs(type_docs);
         if !docs.is_empty() {
             write!(&mut output, "{}", docs).unwrap();
         }
@@ -350,20 +1429,189 @@ impl ScalaContext {
         writeln!(&mut output, "object {} {{", name).unwrap();
 
         for case in &enum_.cases {
-            let case_name = self.to_pascal_case(&case.name);
+            let case_name = self.case_name(name, wit_name, &case.name);
             writeln!(&mut output, "  case object {} extends {}", case_name, name).unwrap();
         }
 
+        if self.opts.emit_wit_names {
+            let cases: Vec<&str> = enum_.cases.iter().map(|c| c.name.as_str()).collect();
+            write!(
+                &mut output,
+                "{}",
+                self.render_wit_names(wit_name, "witCaseNames", &cases, |c| self.case_name(name, wit_name, c))
+            )
+            .unwrap();
+        }
+
+        if self.opts.enum_ordering {
+            // Order cases by their declaration index in WIT
+            let definition = match self.opts.scala_version {
+                ScalaVersion::Scala2 => "implicit val",
+                ScalaVersion::Scala3 => "given",
+            };
+            writeln!(
+                &mut output,
+                "  {} {}: Ordering[{}] = Ordering.by[{}, Int] {{",
+                definition,
+                instance_name(name, "Ordering"),
+                name,
+                name
+            )
+            .unwrap();
+            for (index, case) in enum_.cases.iter().enumerate() {
+                writeln!(&mut output, "    case {} => {}", self.case_name(name, wit_name, &case.name), index).unwrap();
+            }
+            writeln!(&mut output, "  }}").unwrap();
+        }
+
+        write!(&mut output, "{}", self.render_show_instance(name, "value.toString")).unwrap();
+
         writeln!(&mut output, "}}").unwrap();
         output
     }
 
+    /// Get the Scala name of a record field, unless renamed with the `rename`
+    /// option.
+    fn field_name(&self, wit_record_name: &str, field: &str) -> String {
+        self.renamed(&format!("{}.{}", wit_record_name, field))
+            .unwrap_or_else(|| self.to_camel_case(field))
+    }
+
+    /// Get the Scala name configured with the `rename` option for a WIT
+    /// path such as `point.x` or `read-data`, if any.
+    fn renamed(&self, wit_path: &str) -> Option<String> {
+        self.opts
+            .rename
+            .iter()
+            .filter_map(|rename| rename.split_once('='))
+            .find(|(path, _)| *path == wit_path)
+            .map(|(_, name)| self.escape_keyword(name))
+    }
+
+    /// Get the Scala name of a freestanding function or resource instance
+    /// method with the configured getter style.
+    ///
+    /// With `java-bean`, functions taking no arguments (besides `self`) and
+    /// returning a value become getters, `value` turning into `getValue`.
+    /// Names already starting with `get` are kept.
+    pub fn method_name(&self, wit_name: &str, func: &Function) -> String {
+        let args = match func.kind {
+            FunctionKind::Method(_) => func.params.len().saturating_sub(1),
+            _ => func.params.len(),
+        };
+        let is_getter = args == 0
+            && func.result.is_some()
+            && wit_name.split('-').next() != Some("get")
+            && self.opts.getter_style == GetterStyle::JavaBean;
+        if is_getter {
+            self.to_camel_case(&format!("get-{}", wit_name))
+        } else {
+            self.to_camel_case(wit_name)
+        }
+    }
+
+    /// Render the type returned by a function.
+    ///
+    /// With `lease_borrowed_results`, a returned `borrow<T>` is rendered as
+    /// `scala.scalajs.wit.Lease[T]`, signaling that the handle is only lent to
+    /// the caller and must not be stored.
+    pub fn render_return_type(&mut self, resolve: &Resolve, ty: &Type) -> String {
+        let rendered = self.render_type(resolve, ty);
+        match ty {
+            Type::Id(id) if self.opts.lease_borrowed_results => match resolve.types[dealias(resolve, *id)].kind {
+                TypeDefKind::Handle(Handle::Borrow(_)) => format!("scala.scalajs.wit.Lease[{}]", rendered),
+                _ => rendered,
+            },
+            _ => rendered,
+        }
+    }
+
+    /// Get the Scala name of a freestanding function, unless renamed with the
+    /// `rename` option.
+    pub fn function_name(&self, func: &Function) -> String {
+        self.renamed(&func.name)
+            .unwrap_or_else(|| self.method_name(&func.name, func))
+    }
+
+    /// Record an error if functions defined in the same scope get the same
+    /// Scala name, which would generate duplicate `def`s.
+    ///
+    /// `functions` are pairs of WIT and Scala names, and `scope` names where
+    /// they are defined, such as ``resource `stream` ``.
+    pub fn check_function_names(&mut self, scope: &str, functions: &[(&str, String)]) {
+        for (i, (wit_name, scala_name)) in functions.iter().enumerate() {
+            if let Some((previous, _)) = functions[..i].iter().find(|(_, name)| name == scala_name) {
+                self.render_error.get_or_insert_with(|| {
+                    format!(
+                        "functions `{}` and `{}` of {} both generate `def {}`",
+                        previous, wit_name, scope, scala_name
+                    )
+                });
+            }
+        }
+    }
+
+    /// Get the Scala name of a variant or enum case, unless renamed with the
+    /// `rename` option.
+    ///
+    /// A case named like its type would shadow the type inside the companion
+    /// object (`case object Color extends Color`), so it gets a `Case` suffix.
+    fn case_name(&self, type_name: &str, wit_type_name: &str, case: &str) -> String {
+        if let Some(renamed) = self.renamed(&format!("{}.{}", wit_type_name, case)) {
+            return renamed;
+        }
+        let case_name = self.to_pascal_case(case);
+        if case_name == type_name {
+            format!("{}Case", case_name)
+        } else {
+            case_name
+        }
+    }
+
+    /// Render the `witName` constant and the Scala-to-WIT name map for a type's
+    /// fields or cases, indented for a companion object body.
+    fn render_wit_names(
+        &self,
+        wit_name: &str,
+        map_name: &str,
+        members: &[&str],
+        to_scala_name: impl Fn(&str) -> String,
+    ) -> String {
+        let mut output = String::new();
+        writeln!(&mut output, "  val witName: String = {}", annotations::string_literal(wit_name)).unwrap();
+        let entries: Vec<String> = members
+            .iter()
+            .map(|member| {
+                let scala_name = to_scala_name(member);
+                format!(
+                    "{} -> {}",
+                    annotations::string_literal(scala_name.trim_matches('`')),
+                    annotations::string_literal(member)
+                )
+            })
+            .collect();
+        writeln!(
+            &mut output,
+            "  val {}: Map[String, String] = Map({})",
+            map_name,
+            entries.join(", ")
+        )
+        .unwrap();
+        output
+    }
+
     /// Render a flags type as a Scala case class with bitwise operators.
-    fn render_flags(&mut self, name: &str, flags: &Flags, type_docs: &Docs) -> String {
+    fn render_flags(&mut self, name: &str, flags: &Flags, docs: &str) -> String {
         let mut output = String::new();
 
+        if flags.flags.len() > 32 {
+            self.report_unsupported(&format!(
+                "flags with {} members don't fit in the `Int` value of a flags case class",
+                flags.flags.len()
+            ));
+        }
+
         // Generate scaladoc if docs exist
-        let docs = format_docs(type_docs);
         if !docs.is_empty() {
             write!(&mut output, "{}", docs).unwrap();
         }
@@ -400,14 +1648,64 @@ impl ScalaContext {
             name
         )
         .unwrap();
+        // The individual flags that are set, in declaration order
+        let members = if flags.flags.is_empty() {
+            "Iterator.empty".to_string()
+        } else {
+            let flag_values: Vec<String> = flags
+                .flags
+                .iter()
+                .map(|flag| format!("{}.{}", name, self.to_camel_case(&flag.name)))
+                .collect();
+            format!("Iterator({}).filter(flag => contains(flag))", flag_values.join(", "))
+        };
+        writeln!(&mut output, "  def iterator: Iterator[{}] = {}", name, members).unwrap();
+        writeln!(&mut output, "  def toSet: Set[{}] = iterator.toSet", name).unwrap();
+        if self.opts.flags_accessors {
+            for flag in &flags.flags {
+                writeln!(
+                    &mut output,
+                    "  def has{}: Boolean = contains({}.{})",
+                    self.to_pascal_case(&flag.name),
+                    name,
+                    self.to_camel_case(&flag.name)
+                )
+                .unwrap();
+            }
+        }
         writeln!(&mut output, "}}").unwrap();
 
-        writeln!(&mut output, "object {} {{", name).unwrap();
-        for (i, flag) in flags.flags.iter().enumerate() {
-            let flag_name = self.to_camel_case(&flag.name);
-            writeln!(&mut output, "  val {} = {}(1 << {})", flag_name, name, i).unwrap();
+        // Flags are shown by the names of the flags that are set
+        let show_expr = if flags.flags.is_empty() {
+            format!("\"{}()\"", name)
+        } else {
+            let named_flags: Vec<String> = flags
+                .flags
+                .iter()
+                .map(|flag| {
+                    let flag_name = self.to_camel_case(&flag.name);
+                    format!("{}.{} -> {}", name, flag_name, annotations::string_literal(flag_name.trim_matches('`')))
+                })
+                .collect();
+            format!(
+                "Seq({}).collect {{ case (flag, flagName) if value.contains(flag) => flagName }}.mkString(\"{}(\", \" | \", \")\")",
+                named_flags.join(", "),
+                name
+            )
+        };
+        // Flags without members have no constants, so they get no companion
+        // object unless it holds other instances
+        let show = self.render_show_instance(name, &show_expr);
+        if !flags.flags.is_empty() || !show.is_empty() {
+            writeln!(&mut output, "object {} {{", name).unwrap();
+            for (i, flag) in flags.flags.iter().enumerate() {
+                let flag_name = self.to_camel_case(&flag.name);
+                write!(&mut output, "{}", format_docs_with_indent(&flag.docs, 2)).unwrap();
+                writeln!(&mut output, "  val {} = {}(1 << {})", flag_name, name, i).unwrap();
+            }
+            write!(&mut output, "{}", show).unwrap();
+            writeln!(&mut output, "}}").unwrap();
         }
-        writeln!(&mut output, "}}").unwrap();
 
         output
     }
@@ -421,6 +1719,7 @@ impl ScalaContext {
             }
             type_params.push_str(&self.render_type(resolve, ty));
         }
+        self.check_tuple_arity(tuple);
         format!(
             "type {} = scala.scalajs.wit.Tuple{}[{}]",
             name,
@@ -438,8 +1737,36 @@ impl ScalaContext {
         )
     }
 
+    /// Render a named result type as a variant with `Ok` and `Err` cases,
+    /// which has the same representation as the result.
+    fn render_result_variant(
+        &mut self,
+        name: &str,
+        wit_name: &str,
+        result: &Result_,
+        resolve: &Resolve,
+        docs: &str,
+    ) -> String {
+        let case = |case_name: &str, ty: Option<Type>| Case {
+            docs: Docs::default(),
+            name: case_name.to_string(),
+            ty,
+        };
+        let variant = Variant {
+            cases: vec![case("ok", result.ok), case("err", result.err)],
+        };
+        self.render_variant(name, wit_name, &variant, resolve, docs)
+    }
+
     /// Render a result type reference.
     fn render_result_typedef(&mut self, name: &str, result: &Result_, resolve: &Resolve) -> String {
+        format!("type {} = {}", name, self.render_result(resolve, result))
+    }
+
+    /// Render a result type: `result<T, E>` maps to `scala.scalajs.wit.Result[T, E]`,
+    /// or to `scala.util.Either[E, T]` with the `either` result type. Arms
+    /// without a payload are `Unit`.
+    fn render_result(&mut self, resolve: &Resolve, result: &Result_) -> String {
         let ok_type = result
             .ok
             .as_ref()
@@ -450,19 +1777,40 @@ impl ScalaContext {
             .as_ref()
             .map(|t| self.render_type(resolve, t))
             .unwrap_or_else(|| "Unit".to_string());
-        format!(
-            "type {} = scala.scalajs.wit.Result[{}, {}]",
-            name, ok_type, err_type
-        )
+        match self.opts.result_type {
+            ResultType::Runtime => format!("scala.scalajs.wit.Result[{}, {}]", ok_type, err_type),
+            ResultType::Either => format!("scala.util.Either[{}, {}]", err_type, ok_type),
+        }
     }
 
     /// Render a list type reference.
     fn render_list_typedef(&mut self, name: &str, inner: &Type, resolve: &Resolve) -> String {
-        format!(
-            "type {} = Array[{}]",
-            name,
-            self.render_type(resolve, inner)
-        )
+        format!("type {} = {}", name, self.render_list(resolve, inner))
+    }
+
+    /// Render a `list<T>` type.
+    ///
+    /// `list<T>` maps to `Array[T]`, except that `list<u8>` maps to the configured
+    /// `bytes_type` when one is set. Only the list is affected: a standalone `u8`
+    /// is still rendered as `UByte`, so signedness is never silently dropped
+    /// outside of byte buffers. `list<s8>` is always `Array[Byte]` since `s8` is
+    /// already `Byte`. With `optimize_option_lists`, lists of options of
+    /// primitives map to the unboxed `scala.scalajs.wit.OptionArray[T]`.
+    fn render_list(&mut self, resolve: &Resolve, inner: &Type) -> String {
+        if let Some(bytes_type) = &self.opts.bytes_type {
+            if is_primitive(resolve, inner, Type::U8) {
+                return bytes_type.clone();
+            }
+        }
+        if self.opts.char_list_as_string && is_primitive(resolve, inner, Type::Char) {
+            return "String".to_string();
+        }
+        if self.opts.optimize_option_lists {
+            if let Some(payload) = primitive_option_payload(resolve, inner) {
+                return format!("scala.scalajs.wit.OptionArray[{}]", self.render_type(resolve, &payload));
+            }
+        }
+        format!("Array[{}]", self.render_type(resolve, inner))
     }
 
     /// Escape Scala keywords by wrapping them in backticks.
@@ -476,17 +1824,152 @@ impl ScalaContext {
 
     /// Convert a kebab-case name to camelCase (for method names, variables).
     pub fn to_camel_case(&self, name: &str) -> String {
-        self.escape_keyword(&name.to_lower_camel_case())
+        if self.opts.camel_case_acronyms.is_empty() {
+            return self.escape_keyword(&name.to_lower_camel_case());
+        }
+        let converted = name
+            .split(['-', '_'])
+            .filter(|word| !word.is_empty())
+            .enumerate()
+            .map(|(i, word)| match (i, self.find_acronym(word)) {
+                // A leading acronym is lowercased entirely: `http-api` -> `httpApi`
+                (0, _) => word.to_lowercase(),
+                (_, Some(acronym)) => acronym.to_string(),
+                (_, None) => word.to_pascal_case(),
+            })
+            .collect::<String>();
+        self.escape_keyword(&converted)
     }
 
     /// Convert a kebab-case name to PascalCase (for type names, constructors).
     pub fn to_pascal_case(&self, name: &str) -> String {
-        self.escape_keyword(&name.to_pascal_case())
+        if self.opts.camel_case_acronyms.is_empty() {
+            return self.escape_keyword(&name.to_pascal_case());
+        }
+        let converted = name
+            .split(['-', '_'])
+            .filter(|word| !word.is_empty())
+            .map(|word| match self.find_acronym(word) {
+                Some(acronym) => acronym.to_string(),
+                None => word.to_pascal_case(),
+            })
+            .collect::<String>();
+        self.escape_keyword(&converted)
+    }
+
+    /// Look up a configured acronym matching a single name segment (case-insensitively).
+    fn find_acronym(&self, word: &str) -> Option<&str> {
+        self.opts
+            .camel_case_acronyms
+            .iter()
+            .find(|acronym| acronym.eq_ignore_ascii_case(word))
+            .map(|acronym| acronym.as_str())
     }
 
     /// Convert a kebab-case name to snake_case (for package names, file names).
+    ///
+    /// Keywords can't be used as package segments or package object names, so
+    /// they get a trailing underscore (`package` becomes `package_`), which
+    /// also keeps file paths free of backticks. For the same reason, characters
+    /// that can't appear in a Scala identifier are replaced with underscores,
+    /// and a segment starting with a digit gets a leading underscore.
     pub fn to_snake_case(&self, name: &str) -> String {
-        name.to_snake_case()
+        let mut name: String = name
+            .to_snake_case()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+            .collect();
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            name.insert(0, '_');
+        }
+        if self.keywords.is_keyword(&name) {
+            format!("{}_", name)
+        } else {
+            name
+        }
+    }
+
+    /// Get the generator options.
+    pub fn opts(&self) -> &Opts {
+        &self.opts
+    }
+
+    /// Get the package segment that export bindings are placed under.
+    pub fn exports_segment(&self) -> &str {
+        self.opts.exports_segment.as_deref().unwrap_or("exports")
+    }
+
+    /// Get the name a world is generated under. Worlds without a usable name,
+    /// like an anonymous world or `$root`, use the configured default name.
+    pub fn world_name<'a>(&'a self, wit_name: &'a str) -> &'a str {
+        if wit_name.starts_with('$') || self.to_snake_case(wit_name).trim_matches('_').is_empty() {
+            self.opts.default_world_name.as_deref().unwrap_or("world")
+        } else {
+            wit_name
+        }
+    }
+
+    /// Get the Scala name of a resource, decorated with the configured
+    /// resource prefix and suffix.
+    pub fn resource_name(&self, wit_name: &str) -> String {
+        format!(
+            "{}{}{}",
+            self.opts.resource_prefix.as_deref().unwrap_or(""),
+            self.to_pascal_case(wit_name),
+            self.opts.resource_suffix.as_deref().unwrap_or("")
+        )
+    }
+
+    /// Get the package of the runtime referenced by generated code.
+    pub fn runtime_package(&self) -> &str {
+        self.opts
+            .runtime_package
+            .as_deref()
+            .unwrap_or(self.opts.target.runtime_package())
+    }
+
+    /// Get the name of the shared package object of `shared_types`.
+    pub fn shared_types_object(&self) -> &str {
+        self.opts.shared_types_object.as_deref().unwrap_or("types")
+    }
+
+    /// Get the expression implementing imported members.
+    pub fn native_marker(&self) -> String {
+        match &self.opts.native_marker {
+            Some(marker) => marker.clone(),
+            None => format!("{}.native", self.runtime_package()),
+        }
+    }
+
+    /// Get the maximum nesting depth of rendered types.
+    pub fn max_type_depth(&self) -> usize {
+        self.opts.max_type_depth.unwrap_or(100)
+    }
+
+    /// Get the name of a resource method or static method used in its
+    /// annotation, as chosen by the `resource_method_name_style` option.
+    pub fn resource_method_annotation_name<'a>(&self, wit_name: &'a str, func: &'a Function) -> &'a str {
+        match self.opts.resource_method_name_style {
+            ResourceMethodNameStyle::Bare => wit_name,
+            ResourceMethodNameStyle::Qualified => &func.name,
+        }
+    }
+
+    /// Get the name of the generated resource drop method.
+    pub fn drop_method_name(&self) -> &str {
+        self.opts.drop_method_name.as_deref().unwrap_or("close")
+    }
+
+    /// Render the configured prelude lines, followed by a blank line if non-empty.
+    pub fn render_prelude(&self) -> String {
+        let mut output = String::new();
+        if !self.opts.prelude.is_empty() {
+            for line in &self.opts.prelude {
+                writeln!(&mut output, "{}", line).unwrap();
+            }
+            writeln!(&mut output).unwrap();
+        }
+        output
     }
 
     /// Get the base package segments.
@@ -498,6 +1981,39 @@ impl ScalaContext {
             .collect()
     }
 
+    /// Render the Scaladoc of a function, noting the error type of functions
+    /// returning a `result`.
+    pub fn render_function_docs(&mut self, resolve: &Resolve, func: &Function, indent: usize) -> String {
+        let mut tags = Vec::new();
+        if let Some(Type::Id(id)) = &func.result {
+            if let TypeDefKind::Result(result) = &resolve.types[dealias(resolve, *id)].kind {
+                let error = match &result.err {
+                    Some(Type::Id(err_id)) => match &resolve.types[*err_id].name {
+                        Some(name) => Some(self.to_pascal_case(name)),
+                        None => Some(self.render_type(resolve, &Type::Id(*err_id))),
+                    },
+                    Some(ty) => Some(self.render_type(resolve, ty)),
+                    None => None,
+                };
+                let result_type = match self.result_variant_name(resolve, *id) {
+                    Some(name) => format!("a `{}`", name),
+                    None => match self.opts.result_type {
+                        ResultType::Runtime => "a `Result`".to_string(),
+                        ResultType::Either => "an `Either`".to_string(),
+                    },
+                };
+                tags.push(match error {
+                    Some(error) => format!("@return {} with `{}` as its error type", result_type, error),
+                    None => format!("@return {} without an error payload", result_type),
+                });
+            }
+        }
+        if let Stability::Stable { since, .. } = &func.stability {
+            tags.push(format!("@since {}", since));
+        }
+        format_docs_with_tags(&func.docs, &tags, indent)
+    }
+
     /// Render a function signature with annotation (import or export).
     pub fn render_function(
         &mut self,
@@ -506,11 +2022,12 @@ impl ScalaContext {
         is_import: bool,
         namespace: &str,
     ) -> String {
-        let func_name = self.to_camel_case(&func.name);
+        let func_name = self.function_name(func);
         let wit_name = &func.name;
+        let previous_location = self.set_location(Some(format!("function `{}`", func.name)));
 
         // Generate scaladoc if docs exist
-        let docs = format_docs(&func.docs);
+        let docs = self.render_function_docs(resolve, func, 0);
 
         // Collect parameters
         let mut params = Vec::new();
@@ -521,28 +2038,227 @@ impl ScalaContext {
         }
 
         // Render return type
-        let return_type = func.result.as_ref().map(|ty| self.render_type(resolve, ty));
+        let return_type = func.result.as_ref().map(|ty| self.render_return_type(resolve, ty));
+        self.set_location(previous_location);
 
         if is_import {
-            annotations::import_function(
+            let function = annotations::import_function(
                 namespace,
                 wit_name,
                 &func_name,
                 &params,
                 return_type.as_deref(),
                 &docs,
-            )
+            );
+            if !self.opts.wrap_errors {
+                return function;
+            }
+            match self.error_exception(resolve, func) {
+                Some((exception, _)) => format!(
+                    "{}\n{}",
+                    function,
+                    self.render_throwing_wrapper(resolve, func, &func_name, &params, &exception)
+                ),
+                None => function,
+            }
         } else {
-            annotations::export_function(
+            let function = annotations::export_function(
                 namespace,
                 wit_name,
                 &func_name,
                 &params,
                 return_type.as_deref(),
                 &docs,
+            );
+            // Objects can't have abstract members, so the function is stubbed
+            let function = match self.opts.export_freestanding_as {
+                ExportFreestandingAs::Trait => function,
+                ExportFreestandingAs::Object => format!("{} = ???\n", function.trim_end()),
+            };
+            if !self.opts.emit_jsexport {
+                return function;
+            }
+            // The Scala.js export goes right after the WIT export annotation
+            let wit_export = annotations::component_export(namespace, wit_name);
+            let js_export = annotations::js_export(func_name.trim_matches('`'));
+            function.replacen(&wit_export, &format!("{}\n{}", wit_export, js_export), 1)
+        }
+    }
+
+    /// Get the exception thrown by the `--wrap-errors` wrapper of a function
+    /// returning a result with an error payload, along with the Scala error
+    /// type it carries.
+    ///
+    /// Exceptions are named after a named error type (`ErrorCodeException`),
+    /// or after the function for other error types (`ReadException`).
+    pub fn error_exception(&mut self, resolve: &Resolve, func: &Function) -> Option<(String, String)> {
+        let Some(Type::Id(id)) = func.result else {
+            return None;
+        };
+        let TypeDefKind::Result(result) = &resolve.types[dealias(resolve, id)].kind else {
+            return None;
+        };
+        let error = result.err.as_ref()?;
+        let name = match error {
+            Type::Id(id) => resolve.types[*id].name.as_deref(),
+            _ => None,
+        }
+        .unwrap_or(&func.name);
+        Some((format!("{}Exception", self.to_pascal_case(name)), self.render_type(resolve, error)))
+    }
+
+    /// Render the exception hierarchy of the error type of a result-returning
+    /// function with `error_hierarchy`, when it is an enum or a variant: a
+    /// sealed trait named like its `--wrap-errors` exception, with a subclass
+    /// per case and an `apply` picking the subclass of an error value.
+    pub fn render_error_hierarchy(&mut self, resolve: &Resolve, func: &Function) -> Option<String> {
+        if !self.opts.error_hierarchy {
+            return None;
+        }
+        let (exception, error_type) = self.error_exception(resolve, func)?;
+        let Some(Type::Id(result_id)) = func.result else {
+            return None;
+        };
+        let TypeDefKind::Result(result) = &resolve.types[dealias(resolve, result_id)].kind else {
+            return None;
+        };
+        let Some(Type::Id(error_id)) = result.err else {
+            return None;
+        };
+        let error_def = &resolve.types[dealias(resolve, error_id)];
+        let wit_name = error_def.name.as_deref()?;
+        let cases: Vec<(String, Option<Type>)> = match &error_def.kind {
+            TypeDefKind::Enum(enum_) => enum_.cases.iter().map(|case| (case.name.clone(), None)).collect(),
+            TypeDefKind::Variant(variant) => {
+                // Flattened single-case variants have no cases to match on
+                if self.opts.flatten_single_variant && matches!(variant.cases.as_slice(), [Case { ty: Some(_), .. }]) {
+                    return None;
+                }
+                variant.cases.iter().map(|case| (case.name.clone(), case.ty)).collect()
+            }
+            _ => return None,
+        };
+        let type_name = self.to_pascal_case(wit_name);
+
+        let mut output = String::new();
+        writeln!(&mut output, "sealed trait {} extends RuntimeException {{", exception).unwrap();
+        writeln!(&mut output, "  def error: {}", error_type).unwrap();
+        writeln!(&mut output, "  override def getMessage: String = error.toString").unwrap();
+        writeln!(&mut output, "}}").unwrap();
+        writeln!(&mut output, "object {} {{", exception).unwrap();
+        let mut matches = Vec::new();
+        for (case, ty) in &cases {
+            let case_name = self.case_name(&type_name, wit_name, case);
+            let fields: Vec<(String, String)> = match self.destructured_tuple_payload(resolve, ty.as_ref()) {
+                Some(tuple) => tuple
+                    .types
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| (format!("_{}", i), self.render_type(resolve, t)))
+                    .collect(),
+                None => ty
+                    .iter()
+                    .map(|t| ("value".to_string(), self.render_type(resolve, t)))
+                    .collect(),
+            };
+            let declared: Vec<String> = fields.iter().map(|(name, ty)| format!("{}: {}", name, ty)).collect();
+            let names: Vec<&str> = fields.iter().map(|(name, _)| name.as_str()).collect();
+            let value = if fields.is_empty() {
+                format!("{}.{}", error_type, case_name)
+            } else {
+                format!("{}.{}({})", error_type, case_name, names.join(", "))
+            };
+            writeln!(
+                &mut output,
+                "  final case class {}({}) extends {} {{ def error: {} = {} }}",
+                case_name,
+                declared.join(", "),
+                exception,
+                error_type,
+                value
             )
+            .unwrap();
+            matches.push(format!("{} => {}({})", value, case_name, names.join(", ")));
+        }
+        writeln!(&mut output, "  def apply(error: {}): {} = error match {{", error_type, exception).unwrap();
+        for case in &matches {
+            writeln!(&mut output, "    case {}", case).unwrap();
+        }
+        writeln!(&mut output, "  }}").unwrap();
+        writeln!(&mut output, "}}").unwrap();
+        Some(output)
+    }
+
+    /// Get the Scala name of a named result type rendered as a variant with
+    /// `named_result_as_variant`, if the type is one.
+    fn result_variant_name(&self, resolve: &Resolve, id: TypeId) -> Option<String> {
+        let ty = &resolve.types[dealias(resolve, id)];
+        match (&ty.kind, &ty.name) {
+            (TypeDefKind::Result(_), Some(name)) if self.opts.named_result_as_variant => Some(self.to_pascal_case(name)),
+            _ => None,
         }
     }
+
+    /// Render the `--wrap-errors` wrapper of an imported function, returning
+    /// the ok value of its result and throwing `exception` on error.
+    fn render_throwing_wrapper(
+        &mut self,
+        resolve: &Resolve,
+        func: &Function,
+        func_name: &str,
+        params: &[(String, String)],
+        exception: &str,
+    ) -> String {
+        let Some(Type::Id(id)) = func.result else {
+            unreachable!("Not a result-returning function: {}", func.name);
+        };
+        let TypeDefKind::Result(result) = &resolve.types[dealias(resolve, id)].kind else {
+            unreachable!("Not a result-returning function: {}", func.name);
+        };
+        let ok_type = result
+            .ok
+            .as_ref()
+            .map(|t| self.render_type(resolve, t))
+            .unwrap_or_else(|| "Unit".to_string());
+        let declared: Vec<String> = params.iter().map(|(name, ty)| format!("{}: {}", name, ty)).collect();
+        let args: Vec<&str> = params.iter().map(|(name, _)| name.as_str()).collect();
+        let call = format!("{}({})", func_name, args.join(", "));
+
+        let mut output = String::new();
+        writeln!(&mut output, "/** Like [[{}]], but throws [[{}]] on error. */", func_name, exception).unwrap();
+        write!(
+            &mut output,
+            "def {}OrThrow({}): {} = ",
+            func_name.trim_matches('`'),
+            declared.join(", "),
+            ok_type
+        )
+        .unwrap();
+        if self.result_variant_name(resolve, id).is_some() {
+            let variant = self.render_type(resolve, &Type::Id(id));
+            let ok_case = match result.ok {
+                Some(_) => format!("{}.Ok(value) => value", variant),
+                None => format!("{}.Ok => ()", variant),
+            };
+            writeln!(&mut output, "{} match {{", call).unwrap();
+            writeln!(&mut output, "  case {}", ok_case).unwrap();
+            writeln!(&mut output, "  case {}.Err(error) => throw {}(error)", variant, exception).unwrap();
+            writeln!(&mut output, "}}").unwrap();
+            return output;
+        }
+        match self.opts.result_type {
+            ResultType::Runtime => {
+                writeln!(&mut output, "{} match {{", call).unwrap();
+                writeln!(&mut output, "  case scala.scalajs.wit.Ok(value) => value").unwrap();
+                writeln!(&mut output, "  case scala.scalajs.wit.Err(error) => throw {}(error)", exception).unwrap();
+                writeln!(&mut output, "}}").unwrap();
+            }
+            ResultType::Either => {
+                writeln!(&mut output, "{}.fold(error => throw {}(error), identity)", call, exception).unwrap();
+            }
+        }
+        output
+    }
 }
 
 /// Scala keywords that need to be escaped.
@@ -0,0 +1,70 @@
+/// Debug events describing decisions made during code generation.
+///
+/// Events are only recorded with `--debug`, and are written to `debug.log`
+/// next to the generated files, one per line, instead of to stderr.
+use std::fmt;
+
+/// A decision made during code generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebugEvent {
+    /// The namespace derived for an interface and the package it is
+    /// generated into.
+    Namespace {
+        interface: String,
+        namespace: String,
+        package: String,
+    },
+    /// A type referenced by its fully qualified name from another interface
+    /// or from a world.
+    QualifiedType {
+        type_name: String,
+        qualified: String,
+    },
+    /// A generated file and the item that produced it.
+    File { path: String, owner: String },
+    /// The number of generated Scala files and interfaces, recorded last.
+    Summary {
+        scala_files: usize,
+        imports: usize,
+        exports: usize,
+    },
+}
+
+impl fmt::Display for DebugEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Namespace {
+                interface,
+                namespace,
+                package,
+            } => write!(
+                f,
+                "namespace interface={} namespace={} package={}",
+                interface, namespace, package
+            ),
+            Self::QualifiedType { type_name, qualified } => {
+                write!(f, "qualified-type type={} qualified={}", type_name, qualified)
+            }
+            Self::File { path, owner } => write!(f, "file path={} owner={}", path, owner),
+            Self::Summary {
+                scala_files,
+                imports,
+                exports,
+            } => write!(
+                f,
+                "summary scala-files={} imports={} exports={}",
+                scala_files, imports, exports
+            ),
+        }
+    }
+}
+
+/// Render recorded events as the contents of `debug.log`.
+pub fn render_debug_log(events: &[DebugEvent]) -> String {
+    let mut output = String::new();
+    for event in events {
+        output.push_str(&event.to_string());
+        output.push('\n');
+    }
+    output
+}
@@ -4,7 +4,7 @@
 /// - Type definitions (records, variants, enums, flags)
 /// - Function declarations (imports/exports)
 /// - Resource definitions (imports/exports)
-use crate::{ScalaContext, resource, annotations};
+use crate::{ExportFreestandingAs, Opts, ScalaContext, resource, annotations};
 use std::fmt::Write as _;
 use wit_bindgen_core::wit_parser::*;
 
@@ -21,8 +21,9 @@ pub fn render_interface(
 
     // Set current interface context for type qualification
     ctx.set_current_interface(Some(interface_id));
+    ctx.set_exporting(!is_import);
 
-    let package_name = ctx.to_snake_case(interface_name);
+    let package_name = ctx.package_object_name(resolve, interface_id);
     let type_name = ctx.to_pascal_case(interface_name);
     let mut output = String::new();
 
@@ -30,75 +31,79 @@ pub fn render_interface(
     let package_path = get_package_path(ctx, namespace, is_import);
     writeln!(&mut output, "package {}", package_path).unwrap();
     writeln!(&mut output).unwrap();
-
-    // For imports: use package object; for exports: use trait
-    if is_import {
-        writeln!(&mut output, "package object {} {{", package_name).unwrap();
-    } else {
-        writeln!(&mut output, "{}", annotations::component_export_interface()).unwrap();
-        writeln!(&mut output, "trait {} {{", type_name).unwrap();
-    }
-    writeln!(&mut output).unwrap();
+    write!(&mut output, "{}", ctx.render_prelude()).unwrap();
 
     // Generate type definitions
     let mut generated_types = Vec::new();
     for (type_name, type_id) in &interface.types {
+        // Shared types are rendered into the package's `types` object instead
+        if ctx.is_shared_type(resolve, *type_id) {
+            continue;
+        }
         let typedef = ctx.render_typedef(resolve, *type_id);
         if !typedef.is_empty() && !typedef.starts_with("//") {
             generated_types.push((type_name.clone(), typedef));
         }
     }
 
+    // Anonymous aggregate types have no definition of their own in WIT, so
+    // they are defined along with the types of the interface using them
+    for type_id in anonymous_types(resolve, interface_id) {
+        let name = ctx.anonymous_type_name(resolve, type_id);
+        if !generated_types.iter().any(|(generated, _)| *generated == name) {
+            let typedef = ctx.render_typedef(resolve, type_id);
+            generated_types.push((name, typedef));
+        }
+    }
+
+    let mut types_section = String::new();
     if !generated_types.is_empty() {
-        writeln!(&mut output, "  // Type definitions").unwrap();
+        writeln!(&mut types_section, "  // Type definitions").unwrap();
         for (_name, typedef) in &generated_types {
             for line in typedef.lines() {
                 if line.is_empty() {
-                    writeln!(&mut output).unwrap();
+                    writeln!(&mut types_section).unwrap();
                 } else {
-                    writeln!(&mut output, "  {}", line).unwrap();
+                    writeln!(&mut types_section, "  {}", line).unwrap();
                 }
             }
-            writeln!(&mut output).unwrap();
+            writeln!(&mut types_section).unwrap();
         }
     }
 
-    // Generate resources (import only - Scala cannot export resources)
+    // Generate resources (unless they are emitted into their own files)
+    let module_per_resource = ctx.opts().module_per_resource;
     let mut generated_resources = Vec::new();
     for (resource_name, resource_id) in &interface.types {
         let resource_type = &resolve.types[*resource_id];
-        if matches!(resource_type.kind, TypeDefKind::Resource) {
-            if is_import {
-                let resource_code = resource::render_imported_resource(ctx, resolve, *resource_id, namespace);
-                generated_resources.push((resource_name.clone(), resource_code));
+        if matches!(resource_type.kind, TypeDefKind::Resource) && !module_per_resource {
+            let resource_code = if is_import {
+                resource::render_imported_resource(ctx, resolve, *resource_id, namespace)
             } else {
-                // Scala cannot export resources
-                panic!(
-                    "Scala bindings do not support exporting resources. Resource '{}' in interface '{}' cannot be exported.",
-                    resource_name,
-                    interface_name
-                );
-            }
+                resource::render_exported_resource(ctx, resolve, *resource_id, namespace)
+            };
+            generated_resources.push((resource_name.clone(), resource_code));
         }
     }
 
+    let mut resources_section = String::new();
     if !generated_resources.is_empty() {
-        writeln!(&mut output, "  // Resources").unwrap();
+        writeln!(&mut resources_section, "  // Resources").unwrap();
         for (_name, resource_code) in &generated_resources {
             for line in resource_code.lines() {
                 if line.is_empty() {
-                    writeln!(&mut output).unwrap();
+                    writeln!(&mut resources_section).unwrap();
                 } else {
-                    writeln!(&mut output, "  {}", line).unwrap();
+                    writeln!(&mut resources_section, "  {}", line).unwrap();
                 }
             }
-            writeln!(&mut output).unwrap();
+            writeln!(&mut resources_section).unwrap();
         }
     }
 
     // Generate functions (excluding resource methods which are handled above)
     let mut generated_functions = Vec::new();
-    for (func_name, func) in &interface.functions {
+    for func in interface.functions.values() {
         // Skip resource-related functions (they're handled in resource generation)
         if matches!(
             func.kind,
@@ -113,12 +118,174 @@ pub fn render_interface(
             is_import,
             namespace,
         );
-        generated_functions.push((func_name.clone(), func_code));
+        generated_functions.push((func, func_code));
+    }
+    let function_names: Vec<(&str, String)> = generated_functions
+        .iter()
+        .map(|(func, _)| (func.name.as_str(), ctx.function_name(func)))
+        .collect();
+    ctx.check_function_names(&format!("`{}`", namespace), &function_names);
+
+    let functions_section = render_function_sections(&generated_functions);
+    let exceptions_section = if is_import {
+        render_exceptions_section(ctx, resolve, generated_functions.iter().map(|(func, _)| *func))
+    } else {
+        String::new()
+    };
+
+    if is_import || is_type_only(resolve, interface) {
+        // Imports and type-only exports: everything lives in a package object
+        writeln!(&mut output, "package object {} {{", package_name).unwrap();
+        if is_empty(interface) {
+            writeln!(&mut output, "  // `{}` defines no types, functions or resources", namespace).unwrap();
+        }
+        writeln!(&mut output).unwrap();
+        write!(&mut output, "{}", types_section).unwrap();
+        write!(&mut output, "{}", exceptions_section).unwrap();
+        write!(&mut output, "{}", resources_section).unwrap();
+        write!(&mut output, "{}", functions_section).unwrap();
+        writeln!(&mut output, "}}").unwrap();
+    } else if ctx.opts().export_freestanding_as == ExportFreestandingAs::Object {
+        // Exports as an object: functions are stubbed members of an object
+        // called statically, which also holds the types and resources
+        writeln!(&mut output, "{}", annotations::component_export_interface()).unwrap();
+        writeln!(&mut output, "object {} {{", type_name).unwrap();
+        if has_resource_files(resolve, interface, module_per_resource) {
+            writeln!(&mut output, "  import {}._", package_name).unwrap();
+        }
+        writeln!(&mut output).unwrap();
+        write!(&mut output, "{}", types_section).unwrap();
+        write!(&mut output, "{}", resources_section).unwrap();
+        write!(&mut output, "{}", functions_section).unwrap();
+        writeln!(&mut output, "}}").unwrap();
+    } else {
+        // Exports: functions are abstract members of a trait, while types and
+        // resources are placed in its companion object so they can be
+        // referenced outside it
+        let has_companion = !types_section.is_empty() || !resources_section.is_empty();
+        let has_resource_files = has_resource_files(resolve, interface, module_per_resource);
+        writeln!(&mut output, "{}", annotations::component_export_interface()).unwrap();
+        writeln!(&mut output, "trait {} {{", type_name).unwrap();
+        if has_companion {
+            writeln!(&mut output, "  import {}._", type_name).unwrap();
+        }
+        if has_resource_files {
+            writeln!(&mut output, "  import {}._", package_name).unwrap();
+        }
+        writeln!(&mut output).unwrap();
+        write!(&mut output, "{}", functions_section).unwrap();
+        writeln!(&mut output, "}}").unwrap();
+
+        if has_companion {
+            writeln!(&mut output).unwrap();
+            writeln!(&mut output, "object {} {{", type_name).unwrap();
+            writeln!(&mut output).unwrap();
+            write!(&mut output, "{}", types_section).unwrap();
+            write!(&mut output, "{}", resources_section).unwrap();
+            writeln!(&mut output, "}}").unwrap();
+        }
+    }
+
+    output
+}
+
+/// Render the exceptions thrown by the `--wrap-errors` wrappers of imported
+/// functions, one per distinct exception name, along with the exception
+/// hierarchies of their error types with `--error-hierarchy`.
+pub fn render_exceptions_section<'a>(
+    ctx: &mut ScalaContext,
+    resolve: &Resolve,
+    functions: impl Iterator<Item = &'a Function>,
+) -> String {
+    let mut output = String::new();
+    if !ctx.opts().wrap_errors && !ctx.opts().error_hierarchy {
+        return output;
+    }
+    let mut exceptions: Vec<(String, String)> = Vec::new();
+    for func in functions {
+        let Some((name, error_type)) = ctx.error_exception(resolve, func) else {
+            continue;
+        };
+        if exceptions.iter().any(|(existing, _)| *existing == name) {
+            continue;
+        }
+        let definition = match ctx.render_error_hierarchy(resolve, func) {
+            Some(hierarchy) => hierarchy,
+            None if ctx.opts().wrap_errors => format!(
+                "final case class {}(error: {}) extends RuntimeException(error.toString)\n",
+                name, error_type
+            ),
+            None => continue,
+        };
+        exceptions.push((name, definition));
     }
+    if exceptions.is_empty() {
+        return output;
+    }
+    writeln!(&mut output, "  // Exceptions").unwrap();
+    for (_name, definition) in &exceptions {
+        for line in definition.lines() {
+            writeln!(&mut output, "  {}", line).unwrap();
+        }
+    }
+    writeln!(&mut output).unwrap();
+    output
+}
+
+/// Whether the interface only defines types, without functions or resources.
+///
+/// Exports of such interfaces have nothing to implement, so their types are
+/// placed in a package object like imports instead of an export trait.
+pub fn is_type_only(resolve: &Resolve, interface: &Interface) -> bool {
+    interface.functions.is_empty()
+        && !interface
+            .types
+            .values()
+            .any(|id| matches!(resolve.types[*id].kind, TypeDefKind::Resource))
+}
+
+/// Whether the interface defines nothing at all: no types, functions or
+/// resources.
+pub fn is_empty(interface: &Interface) -> bool {
+    interface.types.is_empty() && interface.functions.is_empty()
+}
+
+/// Whether the interface's resources are emitted into their own files.
+fn has_resource_files(resolve: &Resolve, interface: &Interface, module_per_resource: bool) -> bool {
+    module_per_resource
+        && interface
+            .types
+            .values()
+            .any(|id| matches!(resolve.types[*id].kind, TypeDefKind::Resource))
+}
 
-    if !generated_functions.is_empty() {
-        writeln!(&mut output, "  // Functions").unwrap();
-        for (_name, func_code) in &generated_functions {
+/// Lay out generated functions in commented sections with their counts.
+///
+/// Stable functions come first under `// Functions (N)`, followed by one
+/// section per feature gate of unstable functions, in order of appearance.
+pub fn render_function_sections(functions: &[(&Function, String)]) -> String {
+    let mut groups: Vec<(Option<&str>, Vec<&str>)> = Vec::new();
+    for (func, func_code) in functions {
+        let feature = match &func.stability {
+            Stability::Unstable { feature, .. } => Some(feature.as_str()),
+            _ => None,
+        };
+        match groups.iter_mut().find(|(group, _)| *group == feature) {
+            Some((_, codes)) => codes.push(func_code),
+            None => groups.push((feature, vec![func_code])),
+        }
+    }
+    groups.sort_by_key(|(feature, _)| feature.is_some());
+
+    let mut output = String::new();
+    for (feature, codes) in &groups {
+        match feature {
+            Some(feature) => {
+                writeln!(&mut output, "  // Unstable functions: feature `{}` ({})", feature, codes.len()).unwrap()
+            }
+            None => writeln!(&mut output, "  // Functions ({})", codes.len()).unwrap(),
+        }
+        for func_code in codes {
             for line in func_code.lines() {
                 if line.is_empty() {
                     writeln!(&mut output).unwrap();
@@ -129,10 +296,88 @@ pub fn render_interface(
             writeln!(&mut output).unwrap();
         }
     }
+    output
+}
 
-    writeln!(&mut output, "}}").unwrap();
+/// Collect the anonymous records, variants, enums and flags used by the types
+/// and functions of an interface, in order of first use and without duplicates.
+fn anonymous_types(resolve: &Resolve, interface_id: InterfaceId) -> Vec<TypeId> {
+    fn visit(resolve: &Resolve, ty: &Type, found: &mut Vec<TypeId>) {
+        if let Type::Id(id) = ty {
+            let typedef = &resolve.types[*id];
+            // Named types are defined (and visited) by their own interface
+            if typedef.name.is_none() && !found.contains(id) {
+                if matches!(
+                    typedef.kind,
+                    TypeDefKind::Record(_) | TypeDefKind::Variant(_) | TypeDefKind::Enum(_) | TypeDefKind::Flags(_)
+                ) {
+                    found.push(*id);
+                }
+                visit_contents(resolve, &typedef.kind, found);
+            }
+        }
+    }
 
-    output
+    fn visit_contents(resolve: &Resolve, kind: &TypeDefKind, found: &mut Vec<TypeId>) {
+        match kind {
+            TypeDefKind::Record(record) => {
+                for field in &record.fields {
+                    visit(resolve, &field.ty, found);
+                }
+            }
+            TypeDefKind::Variant(variant) => {
+                for ty in variant.cases.iter().filter_map(|case| case.ty.as_ref()) {
+                    visit(resolve, ty, found);
+                }
+            }
+            TypeDefKind::Tuple(tuple) => {
+                for ty in &tuple.types {
+                    visit(resolve, ty, found);
+                }
+            }
+            TypeDefKind::Result(result) => {
+                for ty in result.ok.iter().chain(result.err.iter()) {
+                    visit(resolve, ty, found);
+                }
+            }
+            TypeDefKind::List(ty)
+            | TypeDefKind::FixedSizeList(ty, _)
+            | TypeDefKind::Option(ty)
+            | TypeDefKind::Type(ty) => visit(resolve, ty, found),
+            _ => {}
+        }
+    }
+
+    let interface = &resolve.interfaces[interface_id];
+    let mut found = Vec::new();
+    for type_id in interface.types.values() {
+        visit_contents(resolve, &resolve.types[*type_id].kind, &mut found);
+    }
+    for func in interface.functions.values() {
+        for (_name, ty) in &func.params {
+            visit(resolve, ty, &mut found);
+        }
+        if let Some(ty) = &func.result {
+            visit(resolve, ty, &mut found);
+        }
+    }
+    found
+}
+
+/// Get the Scala names of the types generated for an interface.
+///
+/// This covers type definitions and resources, but not handle aliases which
+/// don't produce any Scala definition of their own.
+pub fn interface_type_names(ctx: &ScalaContext, resolve: &Resolve, interface_id: InterfaceId) -> Vec<String> {
+    resolve.interfaces[interface_id]
+        .types
+        .iter()
+        .filter(|(_name, type_id)| !matches!(resolve.types[**type_id].kind, TypeDefKind::Handle(_)))
+        .map(|(name, type_id)| match resolve.types[*type_id].kind {
+            TypeDefKind::Resource => ctx.resource_name(name),
+            _ => ctx.to_pascal_case(name),
+        })
+        .collect()
 }
 
 /// Get the package path for an interface.
@@ -143,7 +388,7 @@ pub fn get_package_path(ctx: &ScalaContext, namespace: &str, is_import: bool) ->
     let mut segments = ctx.base_package_segments();
 
     if !is_import {
-        segments.push("exports".to_string());
+        segments.push(ctx.exports_segment().to_string());
     }
 
     // Parse namespace which might be like "wasi:io/streams@0.2.0"
@@ -168,6 +413,14 @@ pub fn get_package_path(ctx: &ScalaContext, namespace: &str, is_import: bool) ->
     segments.join(".")
 }
 
+/// Get the package path for an interface without an existing generator context.
+///
+/// This is a convenience for tooling that needs to know where bindings for a
+/// namespace will be placed without running the full code generation.
+pub fn package_for(opts: &Opts, namespace: &str, is_import: bool) -> String {
+    get_package_path(&ScalaContext::new(opts), namespace, is_import)
+}
+
 /// Get the file path for an interface.
 ///
 /// Returns the relative path where the Scala file should be written.
@@ -180,7 +433,7 @@ pub fn get_interface_file_path(
     let mut segments = ctx.base_package_segments();
 
     if !is_import {
-        segments.push("exports".to_string());
+        segments.push(ctx.exports_segment().to_string());
     }
 
     // Parse namespace
@@ -1,11 +1,18 @@
-use anyhow::Result;
-use std::collections::HashSet;
-use wit_bindgen_core::{Files, WorldGenerator, wit_parser::*};
+use anyhow::{Result, bail};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+use debug::DebugEvent;
+use wit_bindgen_core::{Files, WorldGenerator, dealias, wit_parser::*};
 
+pub mod analysis;
 pub mod annotations;
 pub mod context;
+pub mod debug;
 pub mod interface;
 pub mod resource;
+pub mod stub;
+pub mod types;
 pub mod world;
 
 pub use context::ScalaContext;
@@ -21,38 +28,1383 @@ pub struct Opts {
     /// Output directory for bindings
     #[cfg_attr(feature = "clap", arg(long))]
     pub binding_root: Option<String>,
+
+    /// Package segment that export bindings are placed under (defaults to
+    /// "exports")
+    #[cfg_attr(feature = "clap", arg(long, value_name = "NAME"))]
+    pub exports_segment: Option<String>,
+
+    /// Name used for worlds without a usable name of their own, such as an
+    /// anonymous world or `$root` (defaults to "world")
+    #[cfg_attr(feature = "clap", arg(long, value_name = "NAME"))]
+    pub default_world_name: Option<String>,
+
+    /// Namespace used in the annotations of world-level functions and
+    /// resources (defaults to the `$root` module of the world's package, e.g.
+    /// "example:app/$root")
+    #[cfg_attr(feature = "clap", arg(long, value_name = "NAMESPACE"))]
+    pub world_namespace: Option<String>,
+
+    /// Scala version targeted by syntax that differs between versions
+    /// (`2` or `3`)
+    #[cfg_attr(feature = "clap", arg(long, value_name = "VERSION", default_value = "2"))]
+    pub scala_version: ScalaVersion,
+
+    /// Platform targeted by the generated code: `scalajs` (default) or
+    /// `scala-native`, which selects the default runtime package
+    #[cfg_attr(feature = "clap", arg(long, value_name = "TARGET", default_value = "scalajs"))]
+    pub target: Target,
+
+    /// Package of the runtime referenced by generated code (defaults to
+    /// `scala.scalajs.wit` or `scala.scalanative.wit`, depending on the target)
+    #[cfg_attr(feature = "clap", arg(long, value_name = "PACKAGE"))]
+    pub runtime_package: Option<String>,
+
+    /// Expression implementing imported members (defaults to `native` in the
+    /// runtime package)
+    #[cfg_attr(feature = "clap", arg(long, value_name = "EXPR"))]
+    pub native_marker: Option<String>,
+
+    /// Acronyms preserved verbatim in camelCase/PascalCase names (e.g. "HTTP,URL")
+    #[cfg_attr(feature = "clap", arg(long, value_name = "ACRONYM", value_delimiter = ','))]
+    pub camel_case_acronyms: Vec<String>,
+
+    /// Override the Scala name of a record field, enum or variant case
+    /// (`TYPE.MEMBER=NAME`, e.g. `event.type=typ`) or function
+    /// (`FUNCTION=NAME`); may be repeated
+    #[cfg_attr(feature = "clap", arg(long, value_name = "PATH=NAME"))]
+    pub rename: Vec<String>,
+
+    /// Add an annotation above a generated type (`TYPE=@ANNOTATION`, e.g.
+    /// `point=@JsonCodec`); may be repeated, also for the same type
+    #[cfg_attr(feature = "clap", arg(long, value_name = "TYPE=@ANNOTATION"))]
+    pub type_annotation: Vec<String>,
+
+    /// Reference an existing Scala type instead of generating a WIT type
+    /// (`WIT-TYPE=SCALA-TYPE`, e.g.
+    /// `wasi:clocks/wall-clock.datetime=java.time.Instant`); may be repeated
+    #[cfg_attr(feature = "clap", arg(long, value_name = "WIT-TYPE=SCALA-TYPE"))]
+    pub type_map: Vec<String>,
+
+    /// Emit `witName` constants on records, variants and enums, mapping Scala names
+    /// back to their original WIT names
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub emit_wit_names: bool,
+
+    /// Emit a `fieldNames` array in record companion objects, pairing the
+    /// Scala and WIT name of each field in declaration order
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub record_field_names: bool,
+
+    /// Scala type used for `string`: `string` for `String` (default) or
+    /// `wit-string` for the runtime's `scala.scalajs.wit.WitString`
+    #[cfg_attr(feature = "clap", arg(long, value_name = "TYPE", default_value = "string"))]
+    pub string_type: StringType,
+
+    /// Scala type used for `list<u8>` (e.g. "Array[Byte]"); defaults to `Array[UByte]`
+    #[cfg_attr(feature = "clap", arg(long, value_name = "TYPE"))]
+    pub bytes_type: Option<String>,
+
+    /// Map `list<char>` to `String` instead of `Array[Char]`
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub char_list_as_string: bool,
+
+    /// Map `list<option<T>>` of primitive `T` other than `string` to the
+    /// runtime's unboxed `OptionArray[T]` instead of `Array[Optional[T]]`
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub optimize_option_lists: bool,
+
+    /// Scala type used for `result<T, E>`: `runtime` for
+    /// `scala.scalajs.wit.Result[T, E]` (default) or `either` for
+    /// `scala.util.Either[E, T]`
+    #[cfg_attr(feature = "clap", arg(long, value_name = "TYPE", default_value = "runtime"))]
+    pub result_type: ResultType,
+
+    /// Render named result types (`type r = result<T, E>`) as a sealed trait
+    /// with `Ok` and `Err` cases instead of a type alias
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub named_result_as_variant: bool,
+
+    /// Emit a throwing `xOrThrow` wrapper next to imported functions
+    /// returning a result with an error payload, along with the exception
+    /// carrying the error
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub wrap_errors: bool,
+
+    /// Generate an exception hierarchy for enum and variant error types of
+    /// imported functions, with a subclass per case, thrown by the
+    /// `--wrap-errors` wrappers
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub error_hierarchy: bool,
+
+    /// Render record fields one per line instead of on a single line (with a
+    /// trailing comma when targeting Scala 3)
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub multiline_records: bool,
+
+    /// Wrap the parameters of function signatures longer than this many
+    /// characters one per line
+    #[cfg_attr(feature = "clap", arg(long, value_name = "N"))]
+    pub max_line_length: Option<usize>,
+
+    /// Emit `withX(f: X => X)` update helpers on records for fields holding a
+    /// record or an option
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub record_update_helpers: bool,
+
+    /// Render records with a single field as `AnyVal` value classes, for
+    /// zero-cost newtypes
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub collapse_single_field_records: bool,
+
+    /// Emit a `hasX: Boolean` accessor on flags for each flag, to test or
+    /// match which flags are set
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub flags_accessors: bool,
+
+    /// Annotate records with `@SerialVersionUID` and make them extend
+    /// `Serializable`: `auto` derives the UID from the record's structure,
+    /// a number is used as-is
+    #[cfg_attr(feature = "clap", arg(long, value_name = "auto|UID"))]
+    pub serial_version_uid: Option<SerialVersionUid>,
+
+    /// Render variant cases carrying a tuple payload with one field per tuple
+    /// element (`_0`, `_1`, ...) instead of a single `value` field
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub destructure_tuple_payloads: bool,
+
+    /// Render variants with exactly one case carrying a payload as a newtype
+    /// wrapper (`final case class Name(value: T)`) instead of a sealed trait
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub flatten_single_variant: bool,
+
+    /// Emit an implicit `Ordering` in each enum's companion object, ordering
+    /// cases by their WIT declaration order
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub enum_ordering: bool,
+
+    /// Render exported resource methods returning `borrow<T>` as taking a
+    /// continuation (`def withX[R](...)(f: T => R): R`) so the borrow can't escape
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub scoped_borrows: bool,
+
+    /// Render functions returning `borrow<T>` as returning
+    /// `scala.scalajs.wit.Lease[T]`, so the lent handle isn't mistaken for an
+    /// owned one (exported resource methods use `scoped_borrows` if enabled)
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub lease_borrowed_results: bool,
+
+    /// Add Scala.js `@JSExport` annotations to exported functions and
+    /// `@JSExportAll` to exported resources, after their WIT annotations
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub emit_jsexport: bool,
+
+    /// Omit all `@scala.scalajs.wit.annotation` annotations, generating a
+    /// plain API skeleton whose imported members are implemented with `???`
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub no_annotations: bool,
+
+    /// Emit a `Show` instance in the companion object of every record,
+    /// variant, enum and flags type
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub emit_show: bool,
+
+    /// Fully qualified `Show` typeclass used by `--emit-show` (defaults to
+    /// "cats.Show")
+    #[cfg_attr(feature = "clap", arg(long, value_name = "TYPECLASS"))]
+    pub show_typeclass: Option<String>,
+
+    /// Name of the generated resource drop method (defaults to "close")
+    #[cfg_attr(feature = "clap", arg(long, value_name = "NAME"))]
+    pub drop_method_name: Option<String>,
+
+    /// Name used in resource method annotations: `bare` for the method name
+    /// (default) or `qualified` for the WIT function name, e.g.
+    /// `[method]input-stream.read`
+    #[cfg_attr(feature = "clap", arg(long, value_name = "STYLE", default_value = "bare"))]
+    pub resource_method_name_style: ResourceMethodNameStyle,
+
+    /// Names of functions and resource methods taking no arguments and
+    /// returning a value: `plain` (default, `value`) or `java-bean`
+    /// (`getValue`)
+    #[cfg_attr(feature = "clap", arg(long, value_name = "STYLE", default_value = "plain"))]
+    pub getter_style: GetterStyle,
+
+    /// Definition holding exported freestanding functions: `trait` for
+    /// abstract members (default) or `object` for `= ???` stubs the user
+    /// fills in, called statically
+    #[cfg_attr(feature = "clap", arg(long, value_name = "FORM", default_value = "trait"))]
+    pub export_freestanding_as: ExportFreestandingAs,
+
+    /// Prefix added to the Scala names of resources (e.g. "Wit" for `WitInputStream`)
+    #[cfg_attr(feature = "clap", arg(long, value_name = "PREFIX"))]
+    pub resource_prefix: Option<String>,
+
+    /// Suffix added to the Scala names of resources (e.g. "Resource" for
+    /// `InputStreamResource`)
+    #[cfg_attr(feature = "clap", arg(long, value_name = "SUFFIX"))]
+    pub resource_suffix: Option<String>,
+
+    /// Emit the records, variants, enums and flags of each WIT package into a
+    /// shared `package object types` instead of into each interface
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub shared_types: bool,
+
+    /// Name of the shared package object of `shared_types` (default `types`),
+    /// e.g. to avoid clashing with an interface named `types`
+    #[cfg_attr(feature = "clap", arg(long, value_name = "NAME"))]
+    pub shared_types_object: Option<String>,
+
+    /// How the `package object` of an imported interface is named:
+    /// `interface` (default), `last-package-segment` or `fixed=<NAME>`
+    #[cfg_attr(feature = "clap", arg(long, value_name = "STRATEGY", default_value = "interface"))]
+    pub package_object_name: PackageObjectName,
+
+    /// Emit each resource into its own file (in a package named after its
+    /// interface) instead of into the interface file
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub module_per_resource: bool,
+
+    /// Declare the packages below the base package as nested package blocks
+    /// (`package wasi { package io { ... } }`) instead of a single flat
+    /// package clause
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub nested_packages: bool,
+
+    /// Write a `manifest.json` listing every generated interface and its types
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub emit_manifest: bool,
+
+    /// Record code generation decisions, such as derived namespaces and
+    /// qualified type references, in `debug.log`
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub debug: bool,
+
+    /// Write a `wit.scala` file with an `object WitInfo` holding the world
+    /// name, the WIT packages and the generator version
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub emit_wit_info: bool,
+
+    /// Write a `Main.scala` file with an `object Main` holding a `run` stub
+    /// when the world exports `wasi:cli/run`
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub emit_cli_main: bool,
+
+    /// Write a test suite skeleton for each exported trait, with a
+    /// placeholder test per function, using `munit` or `scalatest`
+    #[cfg_attr(feature = "clap", arg(long, value_name = "FRAMEWORK"))]
+    pub emit_test_skeleton: Option<TestFramework>,
+
+    /// Start every generated Scala file with a comment marking it as generated
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub generated_marker: bool,
+
+    /// Text of the `--generated-marker` comment (defaults to
+    /// "GENERATED CODE - DO NOT EDIT")
+    #[cfg_attr(feature = "clap", arg(long, value_name = "TEXT"))]
+    pub generated_marker_text: Option<String>,
+
+    /// Write a `.gitattributes` marking every file of the output directory
+    /// as `linguist-generated`
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub emit_gitattributes: bool,
+
+    /// Write a `runtime_stub.scala` with placeholder definitions of the
+    /// runtime symbols used by the bindings, to type-check them in isolation
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub emit_runtime_stub: bool,
+
+    /// Line inserted after the package declaration of every generated file
+    /// (e.g. "import scala.scalajs.wit.given"); may be repeated
+    #[cfg_attr(feature = "clap", arg(long, value_name = "LINE"))]
+    pub prelude: Vec<String>,
+
+    /// Maximum nesting depth of rendered types, e.g. of `list<list<...>>`
+    /// (defaults to 100)
+    #[cfg_attr(feature = "clap", arg(long, value_name = "DEPTH"))]
+    pub max_type_depth: Option<usize>,
+
+    /// Write an `unused-types.txt` listing the types of imported interfaces
+    /// that no generated function or exported type references
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub report_unused_types: bool,
+
+    /// Write an `UnsignedConversions` object with implicit conversions between
+    /// the unsigned types used by the bindings and their signed values
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub unsigned_conversions: bool,
+
+    /// Fail with a list of every construct the runtime can't represent
+    /// (futures, streams, `char`s beyond UTF-16, tuples of more than 22
+    /// elements, flags of more than 32 members) instead of generating
+    /// `Unknown` or lossy types
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub strict: bool,
+
+    /// Only generate the given interfaces, as `ns:pkg/iface` or `ns:pkg` for
+    /// a whole package; may be repeated
+    #[cfg_attr(feature = "clap", arg(long, value_name = "INTERFACE"))]
+    pub include_interface: Vec<String>,
+
+    /// Skip generating the given interfaces, as `ns:pkg/iface` or `ns:pkg`
+    /// for a whole package; may be repeated
+    #[cfg_attr(feature = "clap", arg(long, value_name = "INTERFACE"))]
+    pub exclude_interface: Vec<String>,
+
+    /// Skip interfaces with no types, functions or resources instead of
+    /// generating an empty package object for them
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub skip_empty_interfaces: bool,
+}
+
+/// Scala version targeted by the generated code.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalaVersion {
+    #[default]
+    Scala2,
+    Scala3,
+}
+
+impl FromStr for ScalaVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2" => Ok(Self::Scala2),
+            "3" => Ok(Self::Scala3),
+            _ => Err(format!("unknown Scala version `{}`: expected `2` or `3`", s)),
+        }
+    }
+}
+
+impl fmt::Display for ScalaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scala2 => write!(f, "2"),
+            Self::Scala3 => write!(f, "3"),
+        }
+    }
+}
+
+/// Platform targeted by the generated code, which selects the default
+/// runtime package.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// Scala.js, with the `scala.scalajs.wit` runtime
+    #[default]
+    ScalaJs,
+    /// Scala Native, with the `scala.scalanative.wit` runtime
+    ScalaNative,
+}
+
+impl Target {
+    /// Get the default runtime package of the target.
+    pub fn runtime_package(&self) -> &'static str {
+        match self {
+            Self::ScalaJs => "scala.scalajs.wit",
+            Self::ScalaNative => "scala.scalanative.wit",
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scalajs" => Ok(Self::ScalaJs),
+            "scala-native" => Ok(Self::ScalaNative),
+            _ => Err(format!("unknown target `{}`: expected `scalajs` or `scala-native`", s)),
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ScalaJs => write!(f, "scalajs"),
+            Self::ScalaNative => write!(f, "scala-native"),
+        }
+    }
+}
+
+/// Scala type used for WIT `result`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultType {
+    /// `scala.scalajs.wit.Result[T, E]`
+    #[default]
+    Runtime,
+    /// `scala.util.Either[E, T]`, with the error on the left
+    Either,
+}
+
+impl FromStr for ResultType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "runtime" => Ok(Self::Runtime),
+            "either" => Ok(Self::Either),
+            _ => Err(format!("unknown result type `{}`: expected `runtime` or `either`", s)),
+        }
+    }
+}
+
+impl fmt::Display for ResultType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Runtime => write!(f, "runtime"),
+            Self::Either => write!(f, "either"),
+        }
+    }
+}
+
+/// Scala type used for WIT `string`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringType {
+    /// `String`
+    #[default]
+    String,
+    /// `scala.scalajs.wit.WitString`, which gives control over the encoding.
+    WitString,
+}
+
+impl FromStr for StringType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "string" => Ok(Self::String),
+            "wit-string" => Ok(Self::WitString),
+            _ => Err(format!("unknown string type `{}`: expected `string` or `wit-string`", s)),
+        }
+    }
+}
+
+impl fmt::Display for StringType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String => write!(f, "string"),
+            Self::WitString => write!(f, "wit-string"),
+        }
+    }
+}
+
+/// Name of resource methods in `@WitResourceMethod` and
+/// `@WitResourceStaticMethod` annotations.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceMethodNameStyle {
+    /// The method name alone, e.g. `read`
+    #[default]
+    Bare,
+    /// The full name of the WIT function, e.g. `[method]input-stream.read`
+    Qualified,
+}
+
+impl FromStr for ResourceMethodNameStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bare" => Ok(Self::Bare),
+            "qualified" => Ok(Self::Qualified),
+            _ => Err(format!(
+                "unknown resource method name style `{}`: expected `bare` or `qualified`",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ResourceMethodNameStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bare => write!(f, "bare"),
+            Self::Qualified => write!(f, "qualified"),
+        }
+    }
+}
+
+/// Names of functions and resource methods taking no arguments and
+/// returning a value.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GetterStyle {
+    /// The camelCase WIT name, e.g. `value`
+    #[default]
+    Plain,
+    /// A JavaBean getter, e.g. `getValue`
+    JavaBean,
+}
+
+impl FromStr for GetterStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Self::Plain),
+            "java-bean" => Ok(Self::JavaBean),
+            _ => Err(format!("unknown getter style `{}`: expected `plain` or `java-bean`", s)),
+        }
+    }
+}
+
+impl fmt::Display for GetterStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Plain => write!(f, "plain"),
+            Self::JavaBean => write!(f, "java-bean"),
+        }
+    }
+}
+
+/// Test framework of the skeletons written with `emit_test_skeleton`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestFramework {
+    /// An `ApiSuite` extending `munit.FunSuite`
+    Munit,
+    /// An `ApiSpec` extending `org.scalatest.funsuite.AnyFunSuite`
+    ScalaTest,
+}
+
+impl TestFramework {
+    /// Get the suffix of the test class, appended to the trait name.
+    fn class_suffix(&self) -> &'static str {
+        match self {
+            Self::Munit => "Suite",
+            Self::ScalaTest => "Spec",
+        }
+    }
+
+    /// Get the fully qualified class test classes extend.
+    fn base_class(&self) -> &'static str {
+        match self {
+            Self::Munit => "munit.FunSuite",
+            Self::ScalaTest => "org.scalatest.funsuite.AnyFunSuite",
+        }
+    }
+}
+
+impl FromStr for TestFramework {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "munit" => Ok(Self::Munit),
+            "scalatest" => Ok(Self::ScalaTest),
+            _ => Err(format!("unknown test framework `{}`: expected `munit` or `scalatest`", s)),
+        }
+    }
+}
+
+impl fmt::Display for TestFramework {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Munit => write!(f, "munit"),
+            Self::ScalaTest => write!(f, "scalatest"),
+        }
+    }
+}
+
+/// Scala definition holding exported freestanding functions.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFreestandingAs {
+    /// Abstract members of a trait implemented by the user
+    #[default]
+    Trait,
+    /// Concrete members of an object, called statically by the runtime
+    Object,
+}
+
+impl FromStr for ExportFreestandingAs {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "trait" => Ok(Self::Trait),
+            "object" => Ok(Self::Object),
+            _ => Err(format!("unknown export form `{}`: expected `trait` or `object`", s)),
+        }
+    }
+}
+
+impl fmt::Display for ExportFreestandingAs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trait => write!(f, "trait"),
+            Self::Object => write!(f, "object"),
+        }
+    }
+}
+
+/// The `serialVersionUID` emitted on records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerialVersionUid {
+    /// Derive a stable UID by hashing the record's WIT name and fields.
+    Auto,
+    /// Use the same UID for every record.
+    Fixed(i64),
+}
+
+impl FromStr for SerialVersionUid {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "auto" {
+            return Ok(Self::Auto);
+        }
+        s.parse()
+            .map(Self::Fixed)
+            .map_err(|_| format!("invalid serial version UID `{}`: expected `auto` or an integer", s))
+    }
+}
+
+impl fmt::Display for SerialVersionUid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Fixed(uid) => write!(f, "{}", uid),
+        }
+    }
+}
+
+/// Strategy for naming the `package object` generated for an imported interface.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub enum PackageObjectName {
+    /// Name it after the interface (`package object streams`).
+    #[default]
+    Interface,
+    /// Name it after the last segment of the WIT package (`package object io`
+    /// for `wasi:io/streams`).
+    LastPackageSegment,
+    /// Use the same fixed name for every interface (`package object api`).
+    Fixed(String),
+}
+
+impl FromStr for PackageObjectName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "interface" => Ok(Self::Interface),
+            "last-package-segment" => Ok(Self::LastPackageSegment),
+            _ => match s.strip_prefix("fixed=") {
+                Some(name) => Ok(Self::Fixed(name.to_string())),
+                None => Err(format!(
+                    "unknown package object name strategy `{}`: expected `interface`, `last-package-segment` or `fixed=<NAME>`",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+impl fmt::Display for PackageObjectName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interface => write!(f, "interface"),
+            Self::LastPackageSegment => write!(f, "last-package-segment"),
+            Self::Fixed(name) => write!(f, "fixed={}", name),
+        }
+    }
+}
+
+/// Whether an interface is `wasi:cli/run` (of any version) with its `run`
+/// function.
+fn is_cli_run(resolve: &Resolve, id: InterfaceId) -> bool {
+    let interface = &resolve.interfaces[id];
+    let Some(package_id) = interface.package else {
+        return false;
+    };
+    let package = &resolve.packages[package_id].name;
+    package.namespace == "wasi"
+        && package.name == "cli"
+        && interface.name.as_deref() == Some("run")
+        && interface.functions.contains_key("run")
+}
+
+/// Check whether a name is a plain Scala identifier.
+fn is_plain_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
 impl Opts {
     pub fn build(&self) -> Box<dyn WorldGenerator> {
         Box::new(Scala::new(self.clone()))
     }
+
+    /// Check that the options are valid and don't conflict with each other.
+    ///
+    /// This is called by the generator before producing any output, but can
+    /// also be called upfront to report errors early.
+    pub fn validate(&self) -> Result<()> {
+        if self.base_package.split('.').any(|segment| segment.is_empty()) {
+            bail!("invalid base package `{}`: package segments must not be empty", self.base_package);
+        }
+        if let Some(name) = &self.drop_method_name {
+            if !is_plain_identifier(name) {
+                bail!("invalid drop method name `{}`: must be a plain Scala identifier", name);
+            }
+        }
+        for rename in &self.rename {
+            match rename.split_once('=') {
+                Some((path, name)) if !path.is_empty() && is_plain_identifier(name) => {}
+                _ => bail!("invalid rename `{}`: expected `PATH=NAME` with a plain Scala identifier", rename),
+            }
+        }
+        for annotation in &self.type_annotation {
+            match annotation.split_once('=') {
+                Some((name, value)) if !name.is_empty() && value.starts_with('@') && value.len() > 1 => {}
+                _ => bail!("invalid type annotation `{}`: expected `TYPE=@ANNOTATION`", annotation),
+            }
+        }
+        for mapping in &self.type_map {
+            match mapping.split_once('=') {
+                Some((wit_type, scala_type))
+                    if wit_type.contains('/') && wit_type.contains('.') && !scala_type.trim().is_empty() => {}
+                _ => bail!("invalid type mapping `{}`: expected `NS:PKG/INTERFACE.TYPE=SCALA-TYPE`", mapping),
+            }
+        }
+        if let Some(package) = &self.runtime_package {
+            if !package.contains('.') || !package.split('.').all(is_plain_identifier) {
+                bail!("invalid runtime package `{}`: expected a nested package such as `scala.scalajs.wit`", package);
+            }
+        }
+        if self.native_marker.as_deref().is_some_and(|marker| marker.trim().is_empty()) {
+            bail!("`--native-marker` must not be empty");
+        }
+        if let Some(prefix) = &self.resource_prefix {
+            if !is_plain_identifier(prefix) {
+                bail!("invalid resource prefix `{}`: must be a plain Scala identifier", prefix);
+            }
+        }
+        if let Some(suffix) = &self.resource_suffix {
+            if suffix.is_empty() || !suffix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                bail!("invalid resource suffix `{}`: must be non-empty and alphanumeric", suffix);
+            }
+        }
+        if let Some(segment) = &self.exports_segment {
+            if !is_plain_identifier(segment) {
+                bail!("invalid exports segment `{}`: must be a plain Scala identifier", segment);
+            }
+        }
+        if let Some(name) = &self.default_world_name {
+            if !is_plain_identifier(name) {
+                bail!("invalid default world name `{}`: must be a plain Scala identifier", name);
+            }
+        }
+        if let Some(namespace) = &self.world_namespace {
+            if namespace.is_empty() || namespace.contains(char::is_whitespace) {
+                bail!("invalid world namespace `{}`: must be non-empty without whitespace", namespace);
+            }
+        }
+        if let Some(name) = &self.shared_types_object {
+            if !is_plain_identifier(name) {
+                bail!("invalid shared types object name `{}`: must be a plain Scala identifier", name);
+            }
+        }
+        if let PackageObjectName::Fixed(name) = &self.package_object_name {
+            if !is_plain_identifier(name) {
+                bail!("invalid package object name `{}`: must be a plain Scala identifier", name);
+            }
+        }
+        if let Some(acronym) = self
+            .camel_case_acronyms
+            .iter()
+            .find(|acronym| acronym.is_empty() || !acronym.chars().all(|c| c.is_ascii_alphanumeric()))
+        {
+            bail!("invalid acronym `{}`: acronyms must be non-empty and alphanumeric", acronym);
+        }
+        if self.emit_jsexport && self.target == Target::ScalaNative {
+            bail!("`--emit-jsexport` conflicts with `--target scala-native`: `@JSExport` annotations only exist on Scala.js");
+        }
+        if self.collapse_single_field_records && self.serial_version_uid.is_some() {
+            bail!("`--collapse-single-field-records` conflicts with `--serial-version-uid`: value classes can't be `Serializable`");
+        }
+        if let Some(text) = &self.generated_marker_text {
+            if text.trim().is_empty() || text.contains('\n') {
+                bail!("invalid generated marker text `{}`: must be a non-empty single line", text.escape_default());
+            }
+        }
+        if self.max_type_depth == Some(0) {
+            bail!("`--max-type-depth` must be at least 1");
+        }
+        if self.bytes_type.as_deref().is_some_and(|ty| ty.trim().is_empty()) {
+            bail!("`--bytes-type` must not be empty");
+        }
+        Ok(())
+    }
+}
+
+/// Parse a WIT source and generate Scala bindings for one of its worlds.
+///
+/// `world` selects a world by name and may be omitted when the source
+/// defines a single world.
+pub fn generate_from_wit(wit_source: &str, world: Option<&str>, opts: &Opts) -> Result<Files> {
+    let mut resolve = Resolve::default();
+    let package = resolve.push_str("input.wit", wit_source)?;
+    let world = resolve.select_world(&[package], world)?;
+    let mut files = Files::default();
+    opts.build().generate(&resolve, world, &mut files)?;
+    Ok(files)
 }
 
 /// Main Scala bindings generator.
 pub struct Scala {
+    opts: Opts,
     context: ScalaContext,
     imports: HashSet<InterfaceId>,
     exports: HashSet<InterfaceId>,
     has_world_imports: bool,
     has_world_exports: bool,
+    /// Generated file paths (lowercased) mapped to the item that produced them.
+    generated_paths: HashMap<String, String>,
+    /// Qualified names of generated import package objects mapped to their interface.
+    package_objects: HashMap<String, String>,
+    /// Generated interfaces, recorded for `--emit-manifest`.
+    manifest: Vec<ManifestEntry>,
+    /// Error from `Opts::validate`, reported before any output is generated.
+    invalid_opts: Option<anyhow::Error>,
+    /// Constructs the runtime can't represent, reported together with `--strict`.
+    unsupported: Vec<String>,
+    /// Unsigned types referenced by generated files, for `--unsigned-conversions`.
+    unsigned_types: HashSet<&'static str>,
+    /// Package segment of the world being generated when `generate_all`
+    /// generates several worlds, which scopes the per-world `Main` and
+    /// `WitInfo` objects and leaves the aggregate files to `generate_all`.
+    world_scope: Option<String>,
+    /// Types of imported interfaces and whether they are used, for `--report-unused-types`.
+    type_usage: Vec<(String, bool)>,
+    /// Number of Scala files pushed, summarized in the debug log.
+    scala_files: usize,
+}
+
+/// A generated interface as listed in the manifest.
+struct ManifestEntry {
+    interface: String,
+    package: String,
+    file: String,
+    is_import: bool,
+    types: Vec<String>,
 }
 
 impl Scala {
-    fn new(opts: Opts) -> Self {
+    pub fn new(opts: Opts) -> Self {
         Self {
+            invalid_opts: opts.validate().err(),
             context: ScalaContext::new(&opts),
+            opts,
             imports: HashSet::new(),
             exports: HashSet::new(),
             has_world_imports: false,
             has_world_exports: false,
+            generated_paths: HashMap::new(),
+            package_objects: HashMap::new(),
+            manifest: Vec::new(),
+            unsupported: Vec::new(),
+            unsigned_types: HashSet::new(),
+            world_scope: None,
+            type_usage: Vec::new(),
+            scala_files: 0,
+        }
+    }
+
+    /// Generate bindings for several worlds at once.
+    ///
+    /// Each world is generated separately with these options. Interface files
+    /// shared by several worlds are written once, while world files, `Main`
+    /// and `WitInfo` are namespaced by world name. The manifest, unused types
+    /// report, unsigned conversions, `.gitattributes` and debug log cover all
+    /// worlds and are written once at the end. Fails if two worlds generate
+    /// the same path with different contents.
+    pub fn generate_all(&self, resolve: &Resolve, worlds: &[WorldId], files: &mut Files) -> Result<()> {
+        let mut written: HashMap<String, (Vec<u8>, String)> = HashMap::new();
+        let mut aggregate = Scala::new(self.opts.clone());
+        for &world in worlds {
+            let world_name = &resolve.worlds[world].name;
+            let mut world_files = Files::default();
+            let mut generator = Scala::new(self.opts.clone());
+            if worlds.len() > 1 {
+                let scope = generator.context.to_snake_case(generator.context.world_name(world_name));
+                generator.world_scope = Some(scope);
+            }
+            generator.generate(resolve, world, &mut world_files)?;
+
+            for entry in generator.manifest.drain(..) {
+                let duplicate = aggregate
+                    .manifest
+                    .iter()
+                    .any(|seen| seen.interface == entry.interface && seen.is_import == entry.is_import);
+                if !duplicate {
+                    aggregate.manifest.push(entry);
+                }
+            }
+            aggregate.unsigned_types.extend(generator.unsigned_types.iter().copied());
+            // A type is unused if no world generating its interface uses it
+            for (type_name, used) in generator.type_usage.drain(..) {
+                match aggregate.type_usage.iter_mut().find(|(seen, _)| *seen == type_name) {
+                    Some((_, seen_used)) => *seen_used |= used,
+                    None => aggregate.type_usage.push((type_name, used)),
+                }
+            }
+            aggregate.context.extend_debug_events(generator.context.take_debug_events());
+            aggregate.imports.extend(generator.imports.iter().copied());
+            aggregate.exports.extend(generator.exports.iter().copied());
+
+            for (path, contents) in world_files.iter() {
+                if let Some((previous, previous_world)) = written.get(path) {
+                    if previous.as_slice() != contents {
+                        bail!(
+                            "`{}` is generated differently for worlds `{}` and `{}`",
+                            path,
+                            previous_world,
+                            world_name
+                        );
+                    }
+                    continue;
+                }
+                if path.ends_with(".scala") {
+                    aggregate.scala_files += 1;
+                }
+                files.push(path, contents);
+                written.insert(path.to_string(), (contents.to_vec(), world_name.clone()));
+            }
         }
+        if worlds.len() > 1 {
+            aggregate.push_aggregate_files(files)?;
+        }
+        Ok(())
+    }
+
+    /// Get the package of the per-world `Main` and `WitInfo` objects, and the
+    /// directory of their files.
+    fn world_scoped_package(&self) -> (String, String) {
+        let mut segments = self.context.base_package_segments();
+        segments.extend(self.world_scope.clone());
+        (segments.join("."), segments.join("/"))
+    }
+
+    /// Push the files describing all of the generated output: the manifest,
+    /// the unused types report, the unsigned conversions, `.gitattributes` and
+    /// the debug log.
+    fn push_aggregate_files(&mut self, files: &mut Files) -> Result<()> {
+        if self.opts.report_unused_types {
+            let mut report = String::new();
+            for (type_name, used) in &self.type_usage {
+                if !used {
+                    writeln!(&mut report, "{}", type_name).unwrap();
+                }
+            }
+            self.push_file(files, "unused-types.txt", "unused types", &report)?;
+        }
+
+        if self.opts.emit_manifest {
+            let manifest = self.render_manifest();
+            self.push_file(files, "manifest.json", "manifest", &manifest)?;
+        }
+
+        if self.opts.unsigned_conversions {
+            if let Some(conversions) = self.render_unsigned_conversions() {
+                let file_path = format!(
+                    "{}/UnsignedConversions.scala",
+                    self.context.base_package_segments().join("/")
+                );
+                self.push_file(files, &file_path, "unsigned conversions", &conversions)?;
+            }
+        }
+
+        if self.opts.emit_gitattributes {
+            self.push_file(files, ".gitattributes", "gitattributes", "* linguist-generated=true\n")?;
+        }
+
+        if self.opts.debug {
+            self.context.record_debug(DebugEvent::Summary {
+                scala_files: self.scala_files,
+                imports: self.imports.len(),
+                exports: self.exports.len(),
+            });
+            let debug_log = debug::render_debug_log(&self.context.take_debug_events());
+            files.push("debug.log", debug_log.as_bytes());
+        }
+
+        Ok(())
+    }
+
+    /// Report invalid options, if any, from the first fallible generation step.
+    fn check_opts(&mut self) -> Result<()> {
+        match self.invalid_opts.take() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether an interface is filtered out by `--include-interface` and
+    /// `--exclude-interface`.
+    fn is_interface_skipped(&self, resolve: &Resolve, id: InterfaceId) -> bool {
+        let interface = &resolve.interfaces[id];
+        let (Some(package_id), Some(interface_name)) = (interface.package, &interface.name) else {
+            return false;
+        };
+        let pkg_name = &resolve.packages[package_id].name;
+        let package = format!("{}:{}", pkg_name.namespace, pkg_name.name);
+        let qualified = format!("{}/{}", package, interface_name);
+        let matches = |pattern: &String| *pattern == package || *pattern == qualified;
+
+        if self.opts.exclude_interface.iter().any(matches) {
+            return true;
+        }
+        !self.opts.include_interface.is_empty() && !self.opts.include_interface.iter().any(matches)
+    }
+
+    /// Reject a generated interface that uses types of a skipped interface,
+    /// since its bindings would reference Scala types that don't exist.
+    fn check_skipped_uses(&self, resolve: &Resolve, id: InterfaceId) -> Result<()> {
+        for (type_name, type_id) in &resolve.interfaces[id].types {
+            let used = dealias(resolve, *type_id);
+            if let TypeOwner::Interface(owner) = resolve.types[used].owner {
+                if owner != id && self.is_interface_skipped(resolve, owner) {
+                    bail!(
+                        "interface `{}` uses type `{}` from skipped interface `{}`",
+                        resolve.id_of(id).unwrap_or_default(),
+                        type_name,
+                        resolve.id_of(owner).unwrap_or_default()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Record the namespace derived for an interface with `--debug`.
+    fn record_namespace(&mut self, interface_name: &str, namespace: &str, is_import: bool) {
+        if !self.opts.debug {
+            return;
+        }
+        let package = interface::get_package_path(&self.context, namespace, is_import);
+        self.context.record_debug(DebugEvent::Namespace {
+            interface: interface_name.to_string(),
+            namespace: namespace.to_string(),
+            package,
+        });
+    }
+
+    /// Record a generated interface for the manifest.
+    fn record_manifest_entry(
+        &mut self,
+        resolve: &Resolve,
+        id: InterfaceId,
+        namespace: &str,
+        file_path: &str,
+        is_import: bool,
+    ) {
+        self.manifest.push(ManifestEntry {
+            interface: namespace.to_string(),
+            package: interface::get_package_path(&self.context, namespace, is_import),
+            file: file_path.to_string(),
+            is_import,
+            types: interface::interface_type_names(&self.context, resolve, id),
+        });
+    }
+
+    /// Render the `Main` object of a CLI component, with a stub of the `run`
+    /// function exported from `wasi:cli/run`.
+    fn render_cli_main(&mut self, resolve: &Resolve, id: InterfaceId, namespace: &str) -> String {
+        self.context.set_current_interface(Some(id));
+        self.context.set_exporting(true);
+        let run = self.context.render_function(resolve, &resolve.interfaces[id].functions["run"], false, namespace);
+        let run = match self.opts.export_freestanding_as {
+            ExportFreestandingAs::Trait => format!("{} = ???\n", run.trim_end()),
+            ExportFreestandingAs::Object => run,
+        };
+
+        let mut output = String::new();
+        writeln!(&mut output, "package {}", self.world_scoped_package().0).unwrap();
+        writeln!(&mut output).unwrap();
+        write!(&mut output, "{}", self.context.render_prelude()).unwrap();
+        writeln!(&mut output, "/** Entrypoint of the CLI component. */").unwrap();
+        writeln!(&mut output, "object Main {{").unwrap();
+        for line in run.lines() {
+            writeln!(&mut output, "  {}", line).unwrap();
+        }
+        writeln!(&mut output, "}}").unwrap();
+        output
+    }
+
+    /// Push the test skeleton of an exported trait with `emit_test_skeleton`,
+    /// with one placeholder test per function.
+    fn push_test_skeleton(
+        &mut self,
+        files: &mut Files,
+        package_path: &str,
+        trait_name: &str,
+        functions: &[String],
+        owner: &str,
+    ) -> Result<()> {
+        let Some(framework) = self.opts.emit_test_skeleton else {
+            return Ok(());
+        };
+        let class_name = format!("{}{}", trait_name, framework.class_suffix());
+
+        let mut output = String::new();
+        writeln!(&mut output, "package {}", package_path).unwrap();
+        writeln!(&mut output).unwrap();
+        write!(&mut output, "{}", self.context.render_prelude()).unwrap();
+        writeln!(&mut output, "/** Tests of the `{}` implementation. */", trait_name).unwrap();
+        writeln!(&mut output, "class {} extends {} {{", class_name, framework.base_class()).unwrap();
+        for (i, function) in functions.iter().enumerate() {
+            if i > 0 {
+                writeln!(&mut output).unwrap();
+            }
+            writeln!(
+                &mut output,
+                "  test({}) {{",
+                annotations::string_literal(function.trim_matches('`'))
+            )
+            .unwrap();
+            writeln!(&mut output, "    ???").unwrap();
+            writeln!(&mut output, "  }}").unwrap();
+        }
+        writeln!(&mut output, "}}").unwrap();
+
+        let file_path = format!("{}/{}.scala", package_path.replace('.', "/"), class_name);
+        self.push_file(files, &file_path, owner, &output)
+    }
+
+    /// Render the `WitInfo` object describing the generated world.
+    fn render_wit_info(&self, resolve: &Resolve, world_id: WorldId) -> String {
+        let packages: Vec<String> = resolve
+            .packages
+            .iter()
+            .map(|(_, package)| annotations::string_literal(&package.name.to_string()))
+            .collect();
+
+        let mut output = String::new();
+        writeln!(&mut output, "package {}", self.world_scoped_package().0).unwrap();
+        writeln!(&mut output).unwrap();
+        write!(&mut output, "{}", self.context.render_prelude()).unwrap();
+        writeln!(&mut output, "/** Metadata about the WIT these bindings were generated from. */").unwrap();
+        writeln!(&mut output, "object WitInfo {{").unwrap();
+        writeln!(
+            &mut output,
+            "  val worldName: String = {}",
+            annotations::string_literal(&resolve.worlds[world_id].name)
+        )
+        .unwrap();
+        writeln!(&mut output, "  val packages: Array[String] = Array({})", packages.join(", ")).unwrap();
+        writeln!(
+            &mut output,
+            "  val generatorVersion: String = {}",
+            annotations::string_literal(env!("CARGO_PKG_VERSION"))
+        )
+        .unwrap();
+        writeln!(&mut output, "}}").unwrap();
+        output
+    }
+
+    /// Render the `UnsignedConversions` object with implicit conversions
+    /// between the unsigned types used by the generated files and the signed
+    /// types they wrap, or `None` if no unsigned type is used.
+    fn render_unsigned_conversions(&self) -> Option<String> {
+        let used: Vec<(&str, &str)> = stub::UNSIGNED_TYPES
+            .iter()
+            .copied()
+            .filter(|(name, _)| self.unsigned_types.contains(name))
+            .collect();
+        if used.is_empty() {
+            return None;
+        }
+
+        let mut output = String::new();
+        writeln!(&mut output, "package {}", self.opts.base_package).unwrap();
+        writeln!(&mut output).unwrap();
+        writeln!(&mut output, "import scala.language.implicitConversions").unwrap();
+        writeln!(&mut output).unwrap();
+        write!(&mut output, "{}", self.context.render_prelude()).unwrap();
+        writeln!(&mut output, "/** Conversions between the unsigned types used by these bindings and their signed values. */").unwrap();
+        writeln!(&mut output, "object UnsignedConversions {{").unwrap();
+        for (name, repr) in used {
+            let unsigned = format!("{}.unsigned.{}", annotations::RUNTIME_PACKAGE, name);
+            let to_unsigned = format!("{}To{}", repr.to_lowercase(), name);
+            let to_signed = format!("{}To{}", name.to_lowercase(), repr);
+            match self.opts.scala_version {
+                ScalaVersion::Scala2 => {
+                    writeln!(
+                        &mut output,
+                        "  implicit def {}(value: {}): {} = new {}(value)",
+                        to_unsigned, repr, unsigned, unsigned
+                    )
+                    .unwrap();
+                    writeln!(&mut output, "  implicit def {}(value: {}): {} = value.value", to_signed, unsigned, repr)
+                        .unwrap();
+                }
+                ScalaVersion::Scala3 => {
+                    writeln!(
+                        &mut output,
+                        "  given {}: Conversion[{}, {}] = new {}(_)",
+                        to_unsigned, repr, unsigned, unsigned
+                    )
+                    .unwrap();
+                    writeln!(&mut output, "  given {}: Conversion[{}, {}] = _.value", to_signed, unsigned, repr)
+                        .unwrap();
+                }
+            }
+        }
+        writeln!(&mut output, "}}").unwrap();
+        Some(output)
+    }
+
+    /// Render the manifest of generated interfaces as JSON.
+    fn render_manifest(&self) -> String {
+        fn quote(s: &str) -> String {
+            format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+
+        let mut output = String::new();
+        writeln!(&mut output, "{{").unwrap();
+        writeln!(&mut output, "  \"interfaces\": [").unwrap();
+        for (i, entry) in self.manifest.iter().enumerate() {
+            let types: Vec<String> = entry.types.iter().map(|t| quote(t)).collect();
+            writeln!(&mut output, "    {{").unwrap();
+            writeln!(&mut output, "      \"interface\": {},", quote(&entry.interface)).unwrap();
+            writeln!(&mut output, "      \"package\": {},", quote(&entry.package)).unwrap();
+            writeln!(&mut output, "      \"file\": {},", quote(&entry.file)).unwrap();
+            writeln!(
+                &mut output,
+                "      \"direction\": {},",
+                quote(if entry.is_import { "import" } else { "export" })
+            )
+            .unwrap();
+            writeln!(&mut output, "      \"types\": [{}]", types.join(", ")).unwrap();
+            let separator = if i + 1 < self.manifest.len() { "," } else { "" };
+            writeln!(&mut output, "    }}{}", separator).unwrap();
+        }
+        writeln!(&mut output, "  ]").unwrap();
+        writeln!(&mut output, "}}").unwrap();
+        output
+    }
+
+    /// Push one file per resource of an interface, for `--module-per-resource`.
+    fn push_resource_files(
+        &mut self,
+        resolve: &Resolve,
+        id: InterfaceId,
+        namespace: &str,
+        is_import: bool,
+        files: &mut Files,
+    ) -> Result<()> {
+        if !self.opts.module_per_resource {
+            return Ok(());
+        }
+        let interface = &resolve.interfaces[id];
+        for (resource_name, resource_id) in &interface.types {
+            if !matches!(resolve.types[*resource_id].kind, TypeDefKind::Resource) {
+                continue;
+            }
+            let content = resource::render_resource_file(
+                &mut self.context,
+                resolve,
+                id,
+                *resource_id,
+                namespace,
+                is_import,
+            );
+            let file_path = resource::get_resource_file_path(
+                &self.context,
+                resolve,
+                id,
+                namespace,
+                resource_name,
+                is_import,
+            );
+            let owner = format!("{}#{}", namespace, resource_name);
+            self.push_file(files, &file_path, &owner, &content)?;
+        }
+        Ok(())
+    }
+
+    /// Record the unsigned types referenced by a generated file.
+    fn record_unsigned_types(&mut self, content: &str) {
+        for (name, _) in stub::UNSIGNED_TYPES {
+            let unsigned = format!("{}.unsigned.{}", annotations::RUNTIME_PACKAGE, name);
+            let used = content.match_indices(&unsigned).any(|(i, _)| {
+                !content[i + unsigned.len()..]
+                    .starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+            });
+            if used {
+                self.unsigned_types.insert(name);
+            }
+        }
+    }
+
+    /// Push a generated file, failing if its path collides with an earlier one.
+    ///
+    /// Paths are compared case-insensitively so that output written to a
+    /// case-insensitive filesystem never silently overwrites another file.
+    fn push_file(&mut self, files: &mut Files, path: &str, owner: &str, content: &str) -> Result<()> {
+        if let Some(err) = self.context.take_render_error() {
+            bail!("failed to generate `{}`: {}", path, err);
+        }
+        for problem in self.context.take_unsupported() {
+            self.unsupported.push(format!("{}, {}", owner, problem));
+        }
+        if let Some(previous) = self.generated_paths.get(&path.to_lowercase()) {
+            bail!(
+                "Scala file path collision: `{}` and `{}` both generate `{}`",
+                previous,
+                owner,
+                path
+            );
+        }
+        self.generated_paths
+            .insert(path.to_lowercase(), owner.to_string());
+        let mut content = content.to_string();
+        if path.ends_with(".scala") {
+            self.scala_files += 1;
+            if self.opts.unsigned_conversions {
+                self.record_unsigned_types(&content);
+            }
+            if self.opts.no_annotations {
+                content = annotations::strip_annotations(&content);
+            }
+            let runtime_package = self.context.runtime_package();
+            let native_marker = self.context.native_marker();
+            if runtime_package != annotations::RUNTIME_PACKAGE || native_marker != annotations::native_marker() {
+                content = annotations::relocate_runtime(&content, runtime_package, &native_marker);
+            }
+            if let Some(max_line_length) = self.opts.max_line_length {
+                content = context::wrap_long_signatures(&content, max_line_length);
+            }
+            if self.opts.nested_packages {
+                content = context::nest_packages(&content, &self.opts.base_package);
+            }
+            if self.opts.generated_marker {
+                let text = self.opts.generated_marker_text.as_deref().unwrap_or("GENERATED CODE - DO NOT EDIT");
+                content = format!("// {}\n{}", text, content);
+            }
+        }
+        files.push(path, context::normalize_output(&content).as_bytes());
+        self.context.record_debug(DebugEvent::File {
+            path: path.to_string(),
+            owner: owner.to_string(),
+        });
+        Ok(())
     }
 }
 
 impl WorldGenerator for Scala {
-    fn preprocess(&mut self, _resolve: &Resolve, _world: WorldId) {
-        // No preprocessing needed
+    fn preprocess(&mut self, resolve: &Resolve, world: WorldId) {
+        let exported_interfaces = resolve.worlds[world]
+            .exports
+            .values()
+            .filter_map(|item| match item {
+                WorldItem::Interface { id, .. } => Some(*id),
+                _ => None,
+            })
+            .collect();
+        self.context.set_exported_interfaces(exported_interfaces);
     }
 
     fn import_interface(
@@ -62,7 +1414,18 @@ impl WorldGenerator for Scala {
         id: InterfaceId,
         files: &mut Files,
     ) -> Result<()> {
-        self.imports.insert(id);
+        self.check_opts()?;
+        if self.is_interface_skipped(resolve, id) {
+            return Ok(());
+        }
+        if self.opts.skip_empty_interfaces && interface::is_empty(&resolve.interfaces[id]) {
+            return Ok(());
+        }
+        // Composed worlds may present an interface more than once
+        if !self.imports.insert(id) {
+            return Ok(());
+        }
+        self.check_skipped_uses(resolve, id)?;
 
         let interface = &resolve.interfaces[id];
         let interface_name = interface
@@ -91,6 +1454,8 @@ impl WorldGenerator for Scala {
             resolve.name_world_key(name)
         };
 
+        self.record_namespace(interface_name, &namespace, true);
+
         // Generate interface content
         let content = interface::render_interface(
             &mut self.context,
@@ -108,7 +1473,26 @@ impl WorldGenerator for Scala {
             true, // is_import
         );
 
-        files.push(&file_path, content.as_bytes());
+        self.push_file(files, &file_path, &namespace, &content)?;
+
+        // Interfaces sharing a package object name would be merged by scalac
+        let package_object = format!(
+            "{}.{}",
+            interface::get_package_path(&self.context, &namespace, true),
+            self.context.package_object_name(resolve, id)
+        );
+        if let Some(previous) = self.package_objects.get(&package_object) {
+            bail!(
+                "Scala package object collision: `{}` and `{}` both generate `package object {}`",
+                previous,
+                namespace,
+                package_object
+            );
+        }
+        self.package_objects.insert(package_object, namespace.clone());
+
+        self.push_resource_files(resolve, id, &namespace, true, files)?;
+        self.record_manifest_entry(resolve, id, &namespace, &file_path, true);
 
         Ok(())
     }
@@ -146,7 +1530,18 @@ impl WorldGenerator for Scala {
         id: InterfaceId,
         files: &mut Files,
     ) -> Result<()> {
-        self.exports.insert(id);
+        self.check_opts()?;
+        if self.is_interface_skipped(resolve, id) {
+            return Ok(());
+        }
+        if self.opts.skip_empty_interfaces && interface::is_empty(&resolve.interfaces[id]) {
+            return Ok(());
+        }
+        // Composed worlds may present an interface more than once
+        if !self.exports.insert(id) {
+            return Ok(());
+        }
+        self.check_skipped_uses(resolve, id)?;
 
         let interface = &resolve.interfaces[id];
         let interface_name = interface
@@ -175,6 +1570,8 @@ impl WorldGenerator for Scala {
             resolve.name_world_key(name)
         };
 
+        self.record_namespace(interface_name, &namespace, false);
+
         // Generate interface content
         let content = interface::render_interface(
             &mut self.context,
@@ -192,7 +1589,28 @@ impl WorldGenerator for Scala {
             false, // is_import = false for exports
         );
 
-        files.push(&file_path, content.as_bytes());
+        self.push_file(files, &file_path, &namespace, &content)?;
+        self.push_resource_files(resolve, id, &namespace, false, files)?;
+        self.record_manifest_entry(resolve, id, &namespace, &file_path, false);
+
+        // Type-only interfaces have no trait to test
+        if !interface::is_type_only(resolve, interface) {
+            let functions: Vec<String> = interface
+                .functions
+                .values()
+                .filter(|func| func.kind == FunctionKind::Freestanding)
+                .map(|func| self.context.function_name(func))
+                .collect();
+            let package_path = interface::get_package_path(&self.context, &namespace, false);
+            let trait_name = self.context.to_pascal_case(interface_name);
+            self.push_test_skeleton(files, &package_path, &trait_name, &functions, &namespace)?;
+        }
+
+        if self.opts.emit_cli_main && is_cli_run(resolve, id) {
+            let main = self.render_cli_main(resolve, id, &namespace);
+            let file_path = format!("{}/Main.scala", self.world_scoped_package().1);
+            self.push_file(files, &file_path, &namespace, &main)?;
+        }
 
         Ok(())
     }
@@ -204,6 +1622,8 @@ impl WorldGenerator for Scala {
         funcs: &[(&str, &Function)],
         _files: &mut Files,
     ) -> Result<()> {
+        self.check_opts()?;
+
         // Mark that we have world-level exports (functions or types)
         if !funcs.is_empty() {
             self.has_world_exports = true;
@@ -212,9 +1632,10 @@ impl WorldGenerator for Scala {
     }
 
     fn finish(&mut self, resolve: &Resolve, world_id: WorldId, files: &mut Files) -> Result<()> {
+        self.check_opts()?;
+
         let world = &resolve.worlds[world_id];
         let world_name = &world.name;
-        let mut generated_count = self.imports.len() + self.exports.len();
 
         // Generate world-level import file if there are world-level imports
         if self.has_world_imports {
@@ -225,8 +1646,7 @@ impl WorldGenerator for Scala {
                 true, // is_import
             ) {
                 let file_path = world::get_world_file_path(&self.context, world_name, true);
-                files.push(&file_path, content.as_bytes());
-                generated_count += 1;
+                self.push_file(files, &file_path, world_name, &content)?;
             }
         }
 
@@ -239,17 +1659,88 @@ impl WorldGenerator for Scala {
                 false, // is_import = false for exports
             ) {
                 let file_path = world::get_world_file_path(&self.context, world_name, false);
-                files.push(&file_path, content.as_bytes());
-                generated_count += 1;
+                self.push_file(files, &file_path, world_name, &content)?;
+
+                let functions: Vec<String> = world
+                    .exports
+                    .values()
+                    .filter_map(|item| match item {
+                        WorldItem::Function(func) if func.kind == FunctionKind::Freestanding => {
+                            Some(self.context.function_name(func))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                if !functions.is_empty() {
+                    let scala_world_name = self.context.world_name(world_name).to_string();
+                    let package_path = world::get_world_package_path(&self.context, &scala_world_name, false);
+                    let trait_name = self.context.to_pascal_case(&scala_world_name);
+                    self.push_test_skeleton(files, &package_path, &trait_name, &functions, world_name)?;
+                }
             }
         }
 
-        eprintln!(
-            "Generated {} Scala files ({} imports, {} exports)",
-            generated_count,
-            self.imports.len(),
-            self.exports.len()
-        );
+        // Generate the shared types of every WIT package with generated interfaces
+        if self.opts.shared_types {
+            for (package_id, package) in resolve.packages.iter() {
+                let interface_ids: Vec<InterfaceId> = package
+                    .interfaces
+                    .values()
+                    .copied()
+                    .filter(|id| self.imports.contains(id) || self.exports.contains(id))
+                    .collect();
+                if let Some(content) =
+                    types::render_shared_types(&mut self.context, resolve, package_id, &interface_ids)?
+                {
+                    let file_path = types::get_shared_types_file_path(&self.context, resolve, package_id);
+                    let owner = format!("{}:{}", package.name.namespace, package.name.name);
+                    // Import package objects live in the same package, e.g. for `wasi:http/types`
+                    let package_object = format!(
+                        "{}.{}",
+                        types::get_shared_types_package_path(&self.context, resolve, package_id),
+                        self.context.shared_types_object()
+                    );
+                    if let Some(previous) = self.package_objects.get(&package_object) {
+                        bail!(
+                            "Scala package object collision: `{}` and the shared types of `{}` both generate `package object {}`; rename the shared object with `--shared-types-object`",
+                            previous,
+                            owner,
+                            package_object
+                        );
+                    }
+                    self.push_file(files, &file_path, &owner, &content)?;
+                    }
+            }
+        }
+
+        if self.opts.emit_runtime_stub {
+            let stub = stub::render_runtime_stub(self.context.runtime_package());
+            self.push_file(files, "runtime_stub.scala", "runtime stub", &stub)?;
+        }
+
+        if self.opts.emit_wit_info {
+            let wit_info = self.render_wit_info(resolve, world_id);
+            let file_path = format!("{}/wit.scala", self.world_scoped_package().1);
+            self.push_file(files, &file_path, world_name, &wit_info)?;
+        }
+
+        if !self.unsupported.is_empty() {
+            bail!(
+                "found {} constructs the runtime can't represent:\n  {}",
+                self.unsupported.len(),
+                self.unsupported.join("\n  ")
+            );
+        }
+
+        if self.opts.report_unused_types {
+            let interfaces: HashSet<InterfaceId> = self.imports.union(&self.exports).copied().collect();
+            self.type_usage = analysis::imported_type_usage(resolve, world_id, &interfaces);
+        }
+
+        // With several worlds, `generate_all` pushes these once for all of them
+        if self.world_scope.is_none() {
+            self.push_aggregate_files(files)?;
+        }
 
         Ok(())
     }
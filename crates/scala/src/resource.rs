@@ -3,9 +3,104 @@
 /// Resources represent opaque handles to objects that can have methods,
 /// constructors, and destructors. This module generates Scala trait-based
 /// representations for both imported and exported resources.
-use crate::{ScalaContext, annotations, context::{format_docs, format_docs_with_indent}};
+use crate::{ScalaContext, annotations, context::format_docs_with_stability, interface};
 use std::fmt::Write as _;
-use wit_bindgen_core::wit_parser::*;
+use wit_bindgen_core::{dealias, wit_parser::*};
+
+/// Generate a file containing a single resource, for `module_per_resource`.
+///
+/// The resource is placed in a package named after its interface. For imports
+/// this is the package defined by the interface's package object, so all of
+/// the interface's types are in scope; for exports the interface's companion
+/// object is imported instead.
+pub fn render_resource_file(
+    ctx: &mut ScalaContext,
+    resolve: &Resolve,
+    interface_id: InterfaceId,
+    resource_id: TypeId,
+    namespace: &str,
+    is_import: bool,
+) -> String {
+    // Set current interface context for type qualification
+    ctx.set_current_interface(Some(interface_id));
+    ctx.set_exporting(!is_import);
+
+    let mut output = String::new();
+    let package_path = get_resource_package_path(ctx, resolve, interface_id, namespace, is_import);
+    writeln!(&mut output, "package {}", package_path).unwrap();
+    writeln!(&mut output).unwrap();
+    write!(&mut output, "{}", ctx.render_prelude()).unwrap();
+
+    // Exported interface types live in the interface's companion object,
+    // which only exists if the interface defines any
+    let has_companion = !is_import
+        && resolve.interfaces[interface_id].types.values().any(|type_id| {
+            if matches!(resolve.types[*type_id].kind, TypeDefKind::Resource) || ctx.is_shared_type(resolve, *type_id) {
+                return false;
+            }
+            let typedef = ctx.render_typedef(resolve, *type_id);
+            !typedef.is_empty() && !typedef.starts_with("//")
+        });
+    if has_companion {
+        writeln!(
+            &mut output,
+            "import {}.{}._",
+            interface::get_package_path(ctx, namespace, is_import),
+            ctx.to_pascal_case(
+                resolve.interfaces[interface_id]
+                    .name
+                    .as_ref()
+                    .expect("Interface must have a name")
+            )
+        )
+        .unwrap();
+        writeln!(&mut output).unwrap();
+    }
+
+    let resource_code = if is_import {
+        render_imported_resource(ctx, resolve, resource_id, namespace)
+    } else {
+        render_exported_resource(ctx, resolve, resource_id, namespace)
+    };
+    write!(&mut output, "{}", resource_code).unwrap();
+
+    output
+}
+
+/// Get the package path for a resource emitted into its own file.
+///
+/// For imports: base.package.namespace.name.{package object}
+/// For exports: base.package.exports.namespace.name.{package object}
+pub fn get_resource_package_path(
+    ctx: &ScalaContext,
+    resolve: &Resolve,
+    interface_id: InterfaceId,
+    namespace: &str,
+    is_import: bool,
+) -> String {
+    format!(
+        "{}.{}",
+        interface::get_package_path(ctx, namespace, is_import),
+        ctx.package_object_name(resolve, interface_id)
+    )
+}
+
+/// Get the file path for a resource emitted into its own file.
+pub fn get_resource_file_path(
+    ctx: &ScalaContext,
+    resolve: &Resolve,
+    interface_id: InterfaceId,
+    namespace: &str,
+    resource_name: &str,
+    is_import: bool,
+) -> String {
+    let package_path = get_resource_package_path(ctx, resolve, interface_id, namespace, is_import);
+    format!(
+        "{}/{}.scala",
+        package_path.replace('.', "/"),
+        ctx.to_snake_case(resource_name)
+    )
+}
 
 /// Generate an imported resource as a Scala trait with companion object.
 ///
@@ -19,12 +114,13 @@ pub fn render_imported_resource(
 ) -> String {
     let resource = &resolve.types[resource_id];
     let resource_name = resource.name.as_ref().expect("Resource must have a name");
-    let scala_name = ctx.to_pascal_case(resource_name);
+    let scala_name = ctx.resource_name(resource_name);
+    check_resource_function_names(ctx, resolve, resource_id, true);
 
     let mut output = String::new();
 
     // Generate scaladoc if docs exist
-    let docs = format_docs(&resource.docs);
+    let docs = format_docs_with_stability(&resource.docs, &resource.stability, 0);
     if !docs.is_empty() {
         write!(&mut output, "{}", docs).unwrap();
     }
@@ -36,105 +132,233 @@ pub fn render_imported_resource(
         annotations::component_resource_import(namespace, resource_name)
     )
     .unwrap();
-    writeln!(&mut output, "trait {} {{", scala_name).unwrap();
+    let methods: Vec<(String, usize, bool)> = resource_functions(resolve, resource)
+        .into_iter()
+        .filter(|func| func.kind == FunctionKind::Method(resource_id))
+        .map(|func| (ctx.method_name(func.item_name(), func), scala_params(func).len(), func.result.is_none()))
+        .collect();
+    // A WIT `close()` method returning nothing already implements `AutoCloseable`,
+    // while one returning a value can't override it
+    let close = methods.iter().find(|(name, params, _)| name == "close" && *params == 0);
+    let auto_closeable = !matches!(close, Some((_, _, false)));
+    if auto_closeable {
+        writeln!(&mut output, "trait {} extends java.lang.AutoCloseable {{", scala_name).unwrap();
+    } else {
+        writeln!(&mut output, "trait {} {{", scala_name).unwrap();
+    }
 
     // Collect instance methods
-    if let TypeOwner::Interface(iface_id) = resource.owner {
-        let iface = &resolve.interfaces[iface_id];
-
-        for (_func_key, func) in &iface.functions {
-            if let FunctionKind::Method(method_resource_id) = func.kind {
-                if method_resource_id == resource_id {
-                    let method = render_resource_method(ctx, resolve, &func.name, func);
-                    write!(&mut output, "{}", method).unwrap();
-                }
+    for func in resource_functions(resolve, resource) {
+        if let FunctionKind::Method(method_resource_id) = func.kind {
+            if method_resource_id == resource_id {
+                let method = render_resource_method(ctx, resolve, func.item_name(), func);
+                write!(&mut output, "{}", method).unwrap();
             }
         }
     }
 
-    // Add drop method
-    let drop_method = render_resource_drop_method();
+    // Add drop method, renamed if a WIT method already uses its name
+    let mut drop_method_name = ctx.drop_method_name().to_string();
+    if methods.iter().any(|(name, _, _)| *name == drop_method_name) {
+        drop_method_name = format!("_{}", drop_method_name);
+    }
+    let drop_method = render_drop_method(&drop_method_name, drop_method_name != "close" && close.is_none());
     write!(&mut output, "{}", drop_method).unwrap();
 
     writeln!(&mut output, "}}").unwrap();
 
-    // Generate companion object for static methods and constructor
-    writeln!(&mut output, "object {} {{", scala_name).unwrap();
-
-    // Check for constructor and static methods
-    if let TypeOwner::Interface(iface_id) = resource.owner {
-        let iface = &resolve.interfaces[iface_id];
-
-        for (_func_key, func) in &iface.functions {
-            match func.kind {
-                FunctionKind::Constructor(ctor_resource_id) if ctor_resource_id == resource_id => {
-                    let ctor = render_resource_constructor(ctx, resolve, &scala_name, func);
-                    write!(&mut output, "{}", ctor).unwrap();
-                }
-                FunctionKind::Static(static_resource_id) if static_resource_id == resource_id => {
-                    let static_method =
-                        render_resource_static_method(ctx, resolve, &func.name, func);
-                    write!(&mut output, "{}", static_method).unwrap();
-                }
-                _ => {}
+    // Collect constructor and static methods for the companion object
+    let mut companion_members = String::new();
+    for func in resource_functions(resolve, resource) {
+        match func.kind {
+            FunctionKind::Constructor(ctor_resource_id) if ctor_resource_id == resource_id => {
+                let ctor = render_resource_constructor(ctx, resolve, &scala_name, func);
+                write!(&mut companion_members, "{}", ctor).unwrap();
             }
+            FunctionKind::Static(static_resource_id) if static_resource_id == resource_id => {
+                let static_method =
+                    render_resource_static_method(ctx, resolve, func.item_name(), func, true);
+                write!(&mut companion_members, "{}", static_method).unwrap();
+            }
+            _ => {}
         }
     }
 
-    writeln!(&mut output, "}}").unwrap();
+    // Marker resources without a constructor or statics get no companion object
+    if !companion_members.is_empty() {
+        writeln!(&mut output, "object {} {{", scala_name).unwrap();
+        write!(&mut output, "{}", companion_members).unwrap();
+        writeln!(&mut output, "}}").unwrap();
+    }
 
     output
 }
 
-/// Render an imported resource instance method.
-pub fn render_resource_method(
+/// Generate an exported resource as an abstract Scala trait with a companion trait.
+///
+/// Exported resources are implemented by guest code. Instance methods are
+/// abstract members of the resource trait, while the constructor and static
+/// methods are abstract members of `{Name}Companion`, which the resource's
+/// companion object is expected to extend.
+pub fn render_exported_resource(
     ctx: &mut ScalaContext,
     resolve: &Resolve,
-    wit_name: &str,
-    func: &Function,
+    resource_id: TypeId,
+    namespace: &str,
 ) -> String {
-    let method_name = ctx.to_camel_case(wit_name);
+    let resource = &resolve.types[resource_id];
+    let resource_name = resource.name.as_ref().expect("Resource must have a name");
+    let scala_name = ctx.resource_name(resource_name);
+    check_resource_function_names(ctx, resolve, resource_id, false);
+
     let mut output = String::new();
 
-    // Generate scaladoc if docs exist (with 2-space indentation for trait body)
-    let docs = format_docs_with_indent(&func.docs, 2);
+    // Generate scaladoc if docs exist
+    let docs = format_docs_with_stability(&resource.docs, &resource.stability, 0);
     if !docs.is_empty() {
         write!(&mut output, "{}", docs).unwrap();
     }
 
+    // Generate the trait with annotation
     writeln!(
         &mut output,
-        "  {}",
-        annotations::component_resource_method(wit_name)
+        "{}",
+        annotations::component_resource_export(namespace, resource_name)
     )
     .unwrap();
-    write!(&mut output, "  def {}(", method_name).unwrap();
+    if ctx.opts().emit_jsexport {
+        writeln!(&mut output, "{}", annotations::js_export_all()).unwrap();
+    }
+    writeln!(&mut output, "trait {} {{", scala_name).unwrap();
 
-    // Render parameters
-    for (i, (param_name, param_ty)) in func.params.iter().enumerate() {
-        if i > 0 {
-            write!(&mut output, ", ").unwrap();
+    for func in resource_functions(resolve, resource) {
+        if let FunctionKind::Method(method_resource_id) = func.kind {
+            if method_resource_id == resource_id {
+                let method = render_exported_resource_method(ctx, resolve, func.item_name(), func);
+                write!(&mut output, "{}", method).unwrap();
+            }
         }
-        let scala_param = ctx.to_camel_case(param_name);
-        let scala_type = ctx.render_type(resolve, param_ty);
-        write!(&mut output, "{}: {}", scala_param, scala_type).unwrap();
     }
 
-    write!(&mut output, ")").unwrap();
+    writeln!(&mut output, "}}").unwrap();
 
-    // Render return type
-    if let Some(ret_ty) = &func.result {
-        let scala_ret = ctx.render_type(resolve, ret_ty);
-        write!(&mut output, ": {}", scala_ret).unwrap();
-    } else {
-        write!(&mut output, ": Unit").unwrap();
+    // Generate companion trait for static methods and constructor
+    writeln!(&mut output, "trait {}Companion {{", scala_name).unwrap();
+
+    for func in resource_functions(resolve, resource) {
+        match func.kind {
+            FunctionKind::Constructor(ctor_resource_id) if ctor_resource_id == resource_id => {
+                let ctor = render_resource_function(
+                    ctx,
+                    resolve,
+                    annotations::component_resource_constructor(),
+                    "apply",
+                    func,
+                    &format!(": {}", scala_name),
+                    false,
+                );
+                write!(&mut output, "{}", ctor).unwrap();
+            }
+            FunctionKind::Static(static_resource_id) if static_resource_id == resource_id => {
+                let static_method =
+                    render_resource_static_method(ctx, resolve, func.item_name(), func, false);
+                write!(&mut output, "{}", static_method).unwrap();
+            }
+            _ => {}
+        }
     }
 
-    writeln!(&mut output, " = {}", annotations::native_marker()).unwrap();
+    writeln!(&mut output, "}}").unwrap();
 
     output
 }
 
+/// Record an error if instance methods or static methods of a resource get
+/// the same Scala name.
+fn check_resource_function_names(ctx: &mut ScalaContext, resolve: &Resolve, resource_id: TypeId, is_import: bool) {
+    let resource = &resolve.types[resource_id];
+    let scope = format!("resource `{}`", resource.name.as_ref().expect("Resource must have a name"));
+    let functions = resource_functions(resolve, resource);
+    let methods: Vec<(&str, String)> = functions
+        .iter()
+        .filter(|func| func.kind == FunctionKind::Method(resource_id))
+        .map(|func| {
+            let name = match scoped_borrow(ctx, resolve, func, is_import) {
+                Some(_) => scoped_method_name(ctx, func.item_name()),
+                None => ctx.method_name(func.item_name(), func),
+            };
+            (func.item_name(), name)
+        })
+        .collect();
+    ctx.check_function_names(&scope, &methods);
+    let statics: Vec<(&str, String)> = functions
+        .iter()
+        .filter(|func| func.kind == FunctionKind::Static(resource_id))
+        .map(|func| (func.item_name(), ctx.to_camel_case(func.item_name())))
+        .collect();
+    ctx.check_function_names(&scope, &statics);
+}
+
+/// Get the functions (methods, constructor and statics) of a resource.
+///
+/// Resources defined in an interface have their functions in that interface,
+/// while resources defined directly in a world have them among the world's
+/// imports.
+fn resource_functions<'a>(resolve: &'a Resolve, resource: &TypeDef) -> Vec<&'a Function> {
+    match resource.owner {
+        TypeOwner::Interface(iface_id) => resolve.interfaces[iface_id].functions.values().collect(),
+        TypeOwner::World(world_id) => resolve.worlds[world_id]
+            .imports
+            .values()
+            .filter_map(|item| match item {
+                WorldItem::Function(func) => Some(func),
+                _ => None,
+            })
+            .collect(),
+        TypeOwner::None => Vec::new(),
+    }
+}
+
+/// Render an imported resource instance method.
+pub fn render_resource_method(
+    ctx: &mut ScalaContext,
+    resolve: &Resolve,
+    wit_name: &str,
+    func: &Function,
+) -> String {
+    let (method_name, result) = render_method_signature(ctx, resolve, wit_name, func, true);
+    render_resource_function(
+        ctx,
+        resolve,
+        &annotations::component_resource_method(ctx.resource_method_annotation_name(wit_name, func)),
+        &method_name,
+        func,
+        &result,
+        true,
+    )
+}
+
+/// Render an exported resource instance method.
+///
+/// Exported methods are implemented by guest code, so they are abstract.
+pub fn render_exported_resource_method(
+    ctx: &mut ScalaContext,
+    resolve: &Resolve,
+    wit_name: &str,
+    func: &Function,
+) -> String {
+    let (method_name, result) = render_method_signature(ctx, resolve, wit_name, func, false);
+    render_resource_function(
+        ctx,
+        resolve,
+        &annotations::component_resource_method(ctx.resource_method_annotation_name(wit_name, func)),
+        &method_name,
+        func,
+        &result,
+        false,
+    )
+}
+
 /// Render an imported resource constructor.
 pub fn render_resource_constructor(
     ctx: &mut ScalaContext,
@@ -142,65 +366,140 @@ pub fn render_resource_constructor(
     scala_name: &str,
     func: &Function,
 ) -> String {
-    let mut output = String::new();
-
-    // Generate scaladoc if docs exist (with 2-space indentation for companion object body)
-    let docs = format_docs_with_indent(&func.docs, 2);
-    if !docs.is_empty() {
-        write!(&mut output, "{}", docs).unwrap();
-    }
+    render_resource_function(
+        ctx,
+        resolve,
+        annotations::component_resource_constructor(),
+        "apply",
+        func,
+        &format!(": {}", scala_name),
+        true,
+    )
+}
 
-    writeln!(
-        &mut output,
-        "  {}",
-        annotations::component_resource_constructor()
+/// Render a resource static method (imported or exported).
+fn render_resource_static_method(
+    ctx: &mut ScalaContext,
+    resolve: &Resolve,
+    wit_name: &str,
+    func: &Function,
+    is_import: bool,
+) -> String {
+    let method_name = ctx.to_camel_case(wit_name);
+    let result = format!(": {}", render_return_type(ctx, resolve, func));
+    render_resource_function(
+        ctx,
+        resolve,
+        &annotations::component_resource_static_method(ctx.resource_method_annotation_name(wit_name, func)),
+        &method_name,
+        func,
+        &result,
+        is_import,
     )
-    .unwrap();
-    write!(&mut output, "  def apply(").unwrap();
+}
 
-    // Render parameters
-    for (i, (param_name, param_ty)) in func.params.iter().enumerate() {
-        if i > 0 {
-            write!(&mut output, ", ").unwrap();
+/// Render the Scala return type of a resource function.
+fn render_return_type(ctx: &mut ScalaContext, resolve: &Resolve, func: &Function) -> String {
+    let previous_location = ctx.set_location(Some(format!("function `{}`", func.name)));
+    let return_type = match &func.result {
+        Some(ret_ty) => ctx.render_return_type(resolve, ret_ty),
+        None => "Unit".to_string(),
+    };
+    ctx.set_location(previous_location);
+    return_type
+}
+
+/// Get the borrowed result of an exported resource method rendered in
+/// continuation style with `scoped_borrows`.
+///
+/// Imported methods are implemented by the runtime, which can only lower the
+/// WIT signature, so they return the borrow directly.
+fn scoped_borrow(ctx: &ScalaContext, resolve: &Resolve, func: &Function, is_import: bool) -> Option<Type> {
+    match func.result {
+        Some(Type::Id(id)) if ctx.opts().scoped_borrows && !is_import => {
+            match resolve.types[dealias(resolve, id)].kind {
+                TypeDefKind::Handle(Handle::Borrow(_)) => Some(Type::Id(id)),
+                _ => None,
+            }
         }
-        let scala_param = ctx.to_camel_case(param_name);
-        let scala_type = ctx.render_type(resolve, param_ty);
-        write!(&mut output, "{}: {}", scala_param, scala_type).unwrap();
+        _ => None,
     }
+}
 
-    write!(&mut output, ")").unwrap();
-    write!(&mut output, ": {}", scala_name).unwrap();
-    writeln!(&mut output, " = {}", annotations::native_marker()).unwrap();
-
-    output
+/// Get the name of a method in continuation style, e.g. `withParent`.
+fn scoped_method_name(ctx: &ScalaContext, wit_name: &str) -> String {
+    format!("with{}", ctx.to_pascal_case(wit_name))
 }
 
-/// Render an imported resource static method.
-fn render_resource_static_method(
+/// Render the name and result of a resource instance method.
+///
+/// With `scoped_borrows`, an exported method returning `borrow<T>` takes a
+/// continuation instead (`def withParent[R](...)(f: Node => R): R`) so that
+/// the borrowed handle can't escape the scope in which the runtime keeps it
+/// alive.
+fn render_method_signature(
     ctx: &mut ScalaContext,
     resolve: &Resolve,
     wit_name: &str,
     func: &Function,
+    is_import: bool,
+) -> (String, String) {
+    match scoped_borrow(ctx, resolve, func, is_import) {
+        Some(ty) => {
+            let previous_location = ctx.set_location(Some(format!("function `{}`", func.name)));
+            let borrowed_type = ctx.render_type(resolve, &ty);
+            ctx.set_location(previous_location);
+            (
+                format!("{}[R]", scoped_method_name(ctx, wit_name)),
+                format!("(f: {} => R): R", borrowed_type),
+            )
+        }
+        None => (
+            ctx.method_name(wit_name, func),
+            format!(": {}", render_return_type(ctx, resolve, func)),
+        ),
+    }
+}
+
+/// Get the parameters of a resource function as seen from Scala.
+///
+/// Instance methods receive the resource as an explicit `self` parameter in
+/// WIT, which is simply `this` in the generated trait.
+fn scala_params(func: &Function) -> &[(String, Type)] {
+    match (&func.kind, func.params.first()) {
+        (FunctionKind::Method(_), Some((name, _))) if name == "self" => &func.params[1..],
+        _ => &func.params,
+    }
+}
+
+/// Render a resource method, constructor or static method with its annotation.
+///
+/// `result` is everything following the parameter list, usually `: {Type}`.
+/// Imported functions are implemented by the runtime (`= scala.scalajs.wit.native`),
+/// while exported ones are left abstract for guest code to implement.
+fn render_resource_function(
+    ctx: &mut ScalaContext,
+    resolve: &Resolve,
+    annotation: &str,
+    method_name: &str,
+    func: &Function,
+    result: &str,
+    is_import: bool,
 ) -> String {
-    let method_name = ctx.to_camel_case(wit_name);
     let mut output = String::new();
 
-    // Generate scaladoc if docs exist (with 2-space indentation for companion object body)
-    let docs = format_docs_with_indent(&func.docs, 2);
+    // Generate scaladoc if docs exist (with 2-space indentation for the trait/object body)
+    let docs = ctx.render_function_docs(resolve, func, 2);
     if !docs.is_empty() {
         write!(&mut output, "{}", docs).unwrap();
     }
 
-    writeln!(
-        &mut output,
-        "  {}",
-        annotations::component_resource_static_method(wit_name)
-    )
-    .unwrap();
+    writeln!(&mut output, "  {}", annotation).unwrap();
     write!(&mut output, "  def {}(", method_name).unwrap();
 
     // Render parameters
-    for (i, (param_name, param_ty)) in func.params.iter().enumerate() {
+    let previous_location = ctx.set_location(Some(format!("function `{}`", func.name)));
+    for (i, (param_name, param_ty)) in scala_params(func).iter().enumerate() {
         if i > 0 {
             write!(&mut output, ", ").unwrap();
         }
@@ -208,25 +507,35 @@ fn render_resource_static_method(
         let scala_type = ctx.render_type(resolve, param_ty);
         write!(&mut output, "{}: {}", scala_param, scala_type).unwrap();
     }
+    ctx.set_location(previous_location);
 
-    write!(&mut output, ")").unwrap();
+    write!(&mut output, "){}", result).unwrap();
 
-    // Render return type
-    if let Some(ret_ty) = &func.result {
-        let scala_ret = ctx.render_type(resolve, ret_ty);
-        write!(&mut output, ": {}", scala_ret).unwrap();
+    if is_import {
+        writeln!(&mut output, " = {}", annotations::native_marker()).unwrap();
     } else {
-        write!(&mut output, ": Unit").unwrap();
+        writeln!(&mut output).unwrap();
     }
 
-    writeln!(&mut output, " = {}", annotations::native_marker()).unwrap();
-
     output
 }
 
 /// Render the resource drop method.
-pub fn render_resource_drop_method() -> String {
+///
+/// Imported resource traits extend `java.lang.AutoCloseable`, so when the drop
+/// method is given a name other than `close`, `close` delegates to it.
+pub fn render_resource_drop_method(drop_method_name: &str) -> String {
+    render_drop_method(drop_method_name, drop_method_name != "close")
+}
+
+/// Render the resource drop method, optionally delegating `close` to it.
+fn render_drop_method(drop_method_name: &str, delegate_close: bool) -> String {
     let mut output = String::new();
+    writeln!(
+        &mut output,
+        "  // Dropping a handle more than once traps; call this at most once."
+    )
+    .unwrap();
     writeln!(
         &mut output,
         "  {}",
@@ -235,9 +544,13 @@ pub fn render_resource_drop_method() -> String {
     .unwrap();
     writeln!(
         &mut output,
-        "  def close(): Unit = {}",
+        "  def {}(): Unit = {}",
+        drop_method_name,
         annotations::native_marker()
     )
     .unwrap();
+    if delegate_close {
+        writeln!(&mut output, "  override def close(): Unit = {}()", drop_method_name).unwrap();
+    }
     output
 }
@@ -0,0 +1,93 @@
+/// Runtime stub generation for type-checking bindings without the runtime.
+///
+/// The stub declares every runtime symbol that generated code can
+/// reference, with placeholder definitions. It is only meant for compiling the
+/// bindings in isolation and must not be linked with the real runtime.
+use std::fmt::Write as _;
+
+/// Largest tuple arity of the runtime, all of which the stub declares.
+pub(crate) const MAX_TUPLE_ARITY: usize = 22;
+
+/// Unsigned wrapper types of the runtime with the signed type they wrap.
+pub(crate) const UNSIGNED_TYPES: &[(&str, &str)] =
+    &[("UByte", "Byte"), ("UShort", "Short"), ("UInt", "Int"), ("ULong", "Long")];
+
+/// Annotations with their parameter lists.
+const ANNOTATIONS: &[(&str, &str)] = &[
+    ("WitImport", "(module: String, name: String)"),
+    ("WitExport", "(module: String, name: String)"),
+    ("WitExportInterface", ""),
+    ("WitRecord", ""),
+    ("WitVariant", ""),
+    ("WitFlags", "(numFlags: Int)"),
+    ("WitResourceImport", "(module: String, name: String)"),
+    ("WitResourceExport", "(module: String, name: String)"),
+    ("WitResourceConstructor", ""),
+    ("WitResourceMethod", "(name: String)"),
+    ("WitResourceStaticMethod", "(name: String)"),
+    ("WitResourceDrop", ""),
+];
+
+/// Render the runtime stub file for the given runtime package.
+pub fn render_runtime_stub(runtime_package: &str) -> String {
+    let (parent, name) = runtime_package
+        .rsplit_once('.')
+        .expect("runtime package must have a parent package");
+
+    let mut output = String::new();
+    writeln!(&mut output, "// Placeholder definitions of the scala-wasm runtime symbols referenced").unwrap();
+    writeln!(&mut output, "// by generated bindings. For type-checking only: do not link with the runtime.").unwrap();
+    writeln!(&mut output).unwrap();
+
+    writeln!(&mut output, "package {} {{", parent).unwrap();
+    writeln!(&mut output, "  package object {} {{", name).unwrap();
+    writeln!(
+        &mut output,
+        "    def native: Nothing = throw new NotImplementedError(\"{}.native\")",
+        runtime_package
+    )
+    .unwrap();
+    writeln!(&mut output, "  }}").unwrap();
+    writeln!(&mut output, "}}").unwrap();
+    writeln!(&mut output).unwrap();
+
+    writeln!(&mut output, "package {} {{", runtime_package).unwrap();
+    writeln!(&mut output, "  sealed trait Result[+T, +E]").unwrap();
+    writeln!(&mut output, "  final case class Ok[+T](value: T) extends Result[T, Nothing]").unwrap();
+    writeln!(&mut output, "  final case class Err[+E](value: E) extends Result[Nothing, E]").unwrap();
+    writeln!(&mut output).unwrap();
+    writeln!(&mut output, "  final class WitString(val value: String) extends AnyVal").unwrap();
+    writeln!(&mut output).unwrap();
+    writeln!(&mut output, "  final class OptionArray[T](val defined: Array[Boolean], val values: Array[T])").unwrap();
+    writeln!(&mut output, "  final class Lease[T](val value: T)").unwrap();
+    writeln!(&mut output).unwrap();
+    for arity in 1..=MAX_TUPLE_ARITY {
+        let type_params: Vec<String> = (1..=arity).map(|i| format!("T{}", i)).collect();
+        let fields: Vec<String> = (1..=arity).map(|i| format!("_{}: T{}", i, i)).collect();
+        writeln!(
+            &mut output,
+            "  final case class Tuple{}[{}]({})",
+            arity,
+            type_params.join(", "),
+            fields.join(", ")
+        )
+        .unwrap();
+    }
+    writeln!(&mut output).unwrap();
+
+    writeln!(&mut output, "  package unsigned {{").unwrap();
+    for (name, repr) in UNSIGNED_TYPES {
+        writeln!(&mut output, "    final class {}(val value: {}) extends AnyVal", name, repr).unwrap();
+    }
+    writeln!(&mut output, "  }}").unwrap();
+    writeln!(&mut output).unwrap();
+
+    writeln!(&mut output, "  package annotation {{").unwrap();
+    for (name, params) in ANNOTATIONS {
+        writeln!(&mut output, "    final class {}{} extends scala.annotation.StaticAnnotation", name, params)
+            .unwrap();
+    }
+    writeln!(&mut output, "  }}").unwrap();
+    writeln!(&mut output, "}}").unwrap();
+    output
+}
@@ -0,0 +1,99 @@
+/// Shared type definitions for all interfaces of a WIT package.
+///
+/// With the `shared_types` option, records, variants, enums and flags are not
+/// emitted into each interface's package object or trait, but collected into a
+/// single `package object types` per WIT package which interfaces reference
+/// (named with the `shared_types_object` option).
+use crate::ScalaContext;
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use wit_bindgen_core::wit_parser::*;
+
+/// Generate the shared `types` package object for a WIT package.
+///
+/// Only the types of the given interfaces are included. Returns `None` if none
+/// of them define a shared type, and an error if two interfaces define types
+/// with the same Scala name.
+pub fn render_shared_types(
+    ctx: &mut ScalaContext,
+    resolve: &Resolve,
+    package_id: PackageId,
+    interface_ids: &[InterfaceId],
+) -> Result<Option<String>> {
+    // Shared types are not part of any interface, so all references are qualified
+    ctx.set_current_interface(None);
+    ctx.set_exporting(false);
+
+    let mut generated_types = Vec::new();
+    let mut owners: HashMap<String, &str> = HashMap::new();
+    for interface_id in interface_ids {
+        let interface = &resolve.interfaces[*interface_id];
+        let interface_name = interface.name.as_deref().expect("Interface must have a name");
+        for (type_name, type_id) in &interface.types {
+            if !ctx.is_shared_type(resolve, *type_id) {
+                continue;
+            }
+            let scala_name = ctx.to_pascal_case(type_name);
+            if let Some(previous) = owners.insert(scala_name.clone(), interface_name) {
+                bail!(
+                    "Shared type `{}` is defined in both interface `{}` and interface `{}`",
+                    scala_name,
+                    previous,
+                    interface_name
+                );
+            }
+            generated_types.push(ctx.render_typedef(resolve, *type_id));
+        }
+    }
+
+    if generated_types.is_empty() {
+        return Ok(None);
+    }
+
+    let mut output = String::new();
+    writeln!(&mut output, "package {}", get_shared_types_package_path(ctx, resolve, package_id)).unwrap();
+    writeln!(&mut output).unwrap();
+    write!(&mut output, "{}", ctx.render_prelude()).unwrap();
+
+    writeln!(&mut output, "package object {} {{", ctx.shared_types_object()).unwrap();
+    writeln!(&mut output).unwrap();
+    writeln!(&mut output, "  // Type definitions").unwrap();
+    for typedef in &generated_types {
+        for line in typedef.lines() {
+            if line.is_empty() {
+                writeln!(&mut output).unwrap();
+            } else {
+                writeln!(&mut output, "  {}", line).unwrap();
+            }
+        }
+        writeln!(&mut output).unwrap();
+    }
+    writeln!(&mut output, "}}").unwrap();
+
+    Ok(Some(output))
+}
+
+/// Get the package path enclosing the shared `types` package object.
+///
+/// This is `base.package.namespace.name`, so the shared types are referenced
+/// as `base.package.namespace.name.types.TypeName`.
+pub fn get_shared_types_package_path(ctx: &ScalaContext, resolve: &Resolve, package_id: PackageId) -> String {
+    let pkg_name = &resolve.packages[package_id].name;
+    let mut segments = ctx.base_package_segments();
+    segments.push(ctx.to_snake_case(&pkg_name.namespace));
+    segments.push(ctx.to_snake_case(&pkg_name.name));
+    segments.join(".")
+}
+
+/// Get the file path for the shared `types` package object of a WIT package.
+pub fn get_shared_types_file_path(ctx: &ScalaContext, resolve: &Resolve, package_id: PackageId) -> String {
+    let pkg_name = &resolve.packages[package_id].name;
+    let mut segments = ctx.base_package_segments();
+    segments.push(ctx.to_snake_case(&pkg_name.namespace));
+    segments.push(ctx.to_snake_case(&pkg_name.name));
+    segments.push(ctx.shared_types_object().to_string());
+
+    let path = segments.join("/");
+    format!("{}/package.scala", path)
+}
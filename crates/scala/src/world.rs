@@ -2,7 +2,7 @@
 ///
 /// Worlds can have top-level imports and exports that are not part of
 /// any interface. These are generated in separate world files.
-use crate::ScalaContext;
+use crate::{ExportFreestandingAs, ScalaContext, annotations, interface, resource};
 use std::fmt::Write as _;
 use wit_bindgen_core::wit_parser::*;
 
@@ -14,73 +14,154 @@ pub fn render_world(
     is_import: bool,
 ) -> Option<String> {
     let world = &resolve.worlds[world_id];
-    let world_name = &world.name;
-    let package_name = ctx.to_snake_case(world_name);
+    let world_name = ctx.world_name(&world.name).to_string();
+    let package_name = ctx.to_snake_case(&world_name);
+
+    // World items are outside of any interface, so interface types are fully qualified
+    ctx.set_current_interface(None);
+    ctx.set_exporting(false);
 
     let mut has_content = false;
     let mut output = String::new();
 
-    // Determine package path
-    let package_path = get_world_package_path(ctx, world_name, is_import);
+    // Imported items live in the world's package object, so the file declares
+    // its enclosing package; the export trait is declared in the world's package
+    let package_path = if is_import {
+        ctx.base_package_segments().join(".")
+    } else {
+        get_world_package_path(ctx, &world_name, is_import)
+    };
     writeln!(&mut output, "package {}", package_path).unwrap();
     writeln!(&mut output).unwrap();
+    write!(&mut output, "{}", ctx.render_prelude()).unwrap();
 
-    writeln!(&mut output, "package object {} {{", package_name).unwrap();
-    writeln!(&mut output).unwrap();
-
-    // Generate top-level types
     if is_import {
-        for (_name, item) in &world.imports {
-            if let WorldItem::Type(type_id) = item {
-                let typedef = ctx.render_typedef(resolve, *type_id);
-                if !typedef.is_empty() && !typedef.starts_with("//") {
-                    has_content = true;
-                    writeln!(&mut output, "  // Type definitions").unwrap();
-                    for line in typedef.lines() {
-                        if line.is_empty() {
-                            writeln!(&mut output).unwrap();
-                        } else {
-                            writeln!(&mut output, "  {}", line).unwrap();
-                        }
+        writeln!(&mut output, "package object {} {{", package_name).unwrap();
+        writeln!(&mut output).unwrap();
+    }
+
+    let items = if is_import {
+        &world.imports
+    } else {
+        &world.exports
+    };
+
+    let namespace = match &ctx.opts().world_namespace {
+        Some(namespace) => namespace.clone(),
+        None => get_world_namespace(resolve, world_id),
+    };
+
+    // Generate top-level types (WIT only allows them among a world's imports)
+    for (_name, item) in items.iter().filter(|_| is_import) {
+        if let WorldItem::Type(type_id) = item {
+            // World-level resources are always imported
+            let typedef = if matches!(resolve.types[*type_id].kind, TypeDefKind::Resource) {
+                resource::render_imported_resource(ctx, resolve, *type_id, &namespace)
+            } else {
+                ctx.render_typedef(resolve, *type_id)
+            };
+            if !typedef.is_empty() && !typedef.starts_with("//") {
+                has_content = true;
+                writeln!(&mut output, "  // Type definitions").unwrap();
+                for line in typedef.lines() {
+                    if line.is_empty() {
+                        writeln!(&mut output).unwrap();
+                    } else {
+                        writeln!(&mut output, "  {}", line).unwrap();
                     }
-                    writeln!(&mut output).unwrap();
                 }
+                writeln!(&mut output).unwrap();
             }
         }
-    } else {
-        for (_name, item) in &world.exports {
-            if let WorldItem::Type(type_id) = item {
-                let typedef = ctx.render_typedef(resolve, *type_id);
-                if !typedef.is_empty() && !typedef.starts_with("//") {
-                    has_content = true;
-                    writeln!(&mut output, "  // Type definitions").unwrap();
-                    for line in typedef.lines() {
-                        if line.is_empty() {
-                            writeln!(&mut output).unwrap();
-                        } else {
-                            writeln!(&mut output, "  {}", line).unwrap();
-                        }
-                    }
-                    writeln!(&mut output).unwrap();
-                }
+    }
+
+    // Generate top-level functions (resource functions are rendered with their resource)
+    let mut generated_functions = Vec::new();
+    for (_name, item) in items {
+        if let WorldItem::Function(func) = item {
+            if matches!(
+                func.kind,
+                FunctionKind::Method(_) | FunctionKind::Constructor(_) | FunctionKind::Static(_)
+            ) {
+                continue;
             }
+            let func_code = ctx.render_function(resolve, func, is_import, &namespace);
+            generated_functions.push((func, func_code));
         }
     }
+    let function_names: Vec<(&str, String)> = generated_functions
+        .iter()
+        .map(|(func, _)| (func.name.as_str(), ctx.function_name(func)))
+        .collect();
+    ctx.check_function_names(&format!("world `{}`", world.name), &function_names);
 
-    writeln!(&mut output, "}}").unwrap();
+    // Imported functions live in the package object, while exported functions
+    // are abstract and therefore need to be declared in a trait.
+    if is_import && !generated_functions.is_empty() {
+        has_content = true;
+        write!(
+            &mut output,
+            "{}",
+            interface::render_exceptions_section(ctx, resolve, generated_functions.iter().map(|(func, _)| *func))
+        )
+        .unwrap();
+        write!(&mut output, "{}", interface::render_function_sections(&generated_functions)).unwrap();
+    }
+
+    if is_import {
+        writeln!(&mut output, "}}").unwrap();
+    }
+
+    if !is_import && !generated_functions.is_empty() {
+        has_content = true;
+        writeln!(&mut output, "{}", annotations::component_export_interface()).unwrap();
+        let definition = match ctx.opts().export_freestanding_as {
+            ExportFreestandingAs::Trait => "trait",
+            ExportFreestandingAs::Object => "object",
+        };
+        writeln!(&mut output, "{} {} {{", definition, ctx.to_pascal_case(&world_name)).unwrap();
+        writeln!(&mut output).unwrap();
+        write!(&mut output, "{}", interface::render_function_sections(&generated_functions)).unwrap();
+        writeln!(&mut output, "}}").unwrap();
+    }
 
     if has_content { Some(output) } else { None }
 }
 
-/// Get the package path for a world.
+/// Get the namespace used in annotations for world-level functions.
+///
+/// World-level functions don't belong to any interface, so they are placed in
+/// the `$root` module of the world's package (e.g. `pkg:ns/$root`), the same
+/// module name other wit-bindgen backends use for top-level items.
+pub fn get_world_namespace(resolve: &Resolve, world_id: WorldId) -> String {
+    let world = &resolve.worlds[world_id];
+    match world.package {
+        Some(package_id) => {
+            let pkg_name = &resolve.packages[package_id].name;
+            // Format: "namespace:name/$root@version"
+            if let Some(version) = &pkg_name.version {
+                format!("{}:{}/$root@{}", pkg_name.namespace, pkg_name.name, version)
+            } else {
+                format!("{}:{}/$root", pkg_name.namespace, pkg_name.name)
+            }
+        }
+        None => "$root".to_string(),
+    }
+}
+
+/// Get the package path for a world: `{base}.{world}`, or
+/// `{base}.exports.{world}` for exports.
+///
+/// Imported world items are members of the `package object {world}` declared
+/// in the base package, while the export trait is declared in this package.
 pub fn get_world_package_path(ctx: &ScalaContext, world_name: &str, is_import: bool) -> String {
     let mut segments = ctx.base_package_segments();
 
     if !is_import {
-        segments.push("exports".to_string());
+        segments.push(ctx.exports_segment().to_string());
     }
 
-    segments.push(ctx.to_snake_case(world_name));
+    segments.push(ctx.to_snake_case(ctx.world_name(world_name)));
 
     segments.join(".")
 }
@@ -90,10 +171,10 @@ pub fn get_world_file_path(ctx: &ScalaContext, world_name: &str, is_import: bool
     let mut segments = ctx.base_package_segments();
 
     if !is_import {
-        segments.push("exports".to_string());
+        segments.push(ctx.exports_segment().to_string());
     }
 
-    segments.push(ctx.to_snake_case(world_name));
+    segments.push(ctx.to_snake_case(ctx.world_name(world_name)));
 
     let path = segments.join("/");
     format!("{}/package.scala", path)
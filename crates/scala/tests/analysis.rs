@@ -0,0 +1,60 @@
+use std::collections::HashSet;
+use wit_bindgen_core::wit_parser::Resolve;
+use wit_bindgen_scala::analysis::unused_imported_types;
+
+#[test]
+fn test_unused_imported_types() {
+    let mut resolve = Resolve::default();
+    let pkg = resolve
+        .push_str(
+            "test.wit",
+            r#"
+                package test:unused;
+
+                interface shapes {
+                    record point {
+                        x: s32,
+                    }
+
+                    record box {
+                        corner: point,
+                    }
+                }
+
+                interface api {
+                    use shapes.{point};
+
+                    enum tag {
+                        a,
+                        b,
+                    }
+
+                    record labeled {
+                        at: point,
+                        tag: tag,
+                    }
+
+                    record unused-record {
+                        id: u32,
+                    }
+
+                    resource conn {
+                        send: func(payload: list<labeled>);
+                    }
+                }
+
+                world test {
+                    import api;
+                }
+            "#,
+        )
+        .unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+    let interfaces: HashSet<_> = resolve.interfaces.iter().map(|(id, _)| id).collect();
+
+    // `point` is reached from the resource method through `labeled`
+    assert_eq!(
+        unused_imported_types(&resolve, world, &interfaces),
+        vec!["test:unused/shapes#box", "test:unused/api#unused-record"]
+    );
+}
@@ -48,6 +48,14 @@ fn test_component_resource_import() {
     );
 }
 
+#[test]
+fn test_component_resource_export() {
+    assert_eq!(
+        component_resource_export("example:state/counters", "counter"),
+        "@scala.scalajs.wit.annotation.WitResourceExport(\"example:state/counters\", \"counter\")"
+    );
+}
+
 #[test]
 fn test_component_resource_method() {
     assert_eq!(
@@ -117,3 +125,39 @@ fn test_export_function() {
     assert!(result.contains("def handleRequest(req: Request): Response"));
     assert!(!result.contains("native")); // Export functions don't have native marker
 }
+
+#[test]
+fn test_js_export() {
+    assert_eq!(js_export("run"), "@scala.scalajs.js.annotation.JSExport(\"run\")");
+    assert_eq!(js_export_all(), "@scala.scalajs.js.annotation.JSExportAll");
+}
+
+#[test]
+fn test_annotation_names_are_escaped() {
+    assert_eq!(string_literal("wasi:io/streams@0.2.0"), "\"wasi:io/streams@0.2.0\"");
+    assert_eq!(string_literal("say-\"hi\"\\now"), "\"say-\\\"hi\\\"\\\\now\"");
+    assert_eq!(string_literal("a\nb"), "\"a\\nb\"");
+
+    assert_eq!(
+        component_import("test:w/$root", "[method]my-resource.get-\"x\""),
+        "@scala.scalajs.wit.annotation.WitImport(\"test:w/$root\", \"[method]my-resource.get-\\\"x\\\"\")"
+    );
+    assert_eq!(
+        component_resource_method("back\\slash"),
+        "@scala.scalajs.wit.annotation.WitResourceMethod(\"back\\\\slash\")"
+    );
+}
+
+#[test]
+fn test_rewrites_skip_comments_and_literals() {
+    let content = "/** Uses `scala.scalajs.wit.native`, like\n *  @scala.scalajs.wit.annotation.WitImport members\n */\n@scala.scalajs.wit.annotation.WitImport(\"a:b/c\", \"scala.scalajs.wit.x\")\ndef f(c: Char = '\"'): scala.scalajs.wit.unsigned.UInt = scala.scalajs.wit.native // scala.scalajs.wit.native\n";
+
+    assert_eq!(
+        relocate_runtime(content, "scala.scalanative.wit", "extern"),
+        "/** Uses `scala.scalajs.wit.native`, like\n *  @scala.scalajs.wit.annotation.WitImport members\n */\n@scala.scalanative.wit.annotation.WitImport(\"a:b/c\", \"scala.scalajs.wit.x\")\ndef f(c: Char = '\"'): scala.scalanative.wit.unsigned.UInt = extern // scala.scalajs.wit.native\n"
+    );
+    assert_eq!(
+        strip_annotations(content),
+        "/** Uses `scala.scalajs.wit.native`, like\n *  @scala.scalajs.wit.annotation.WitImport members\n */\ndef f(c: Char = '\"'): scala.scalajs.wit.unsigned.UInt = ??? // scala.scalajs.wit.native\n"
+    );
+}
@@ -1,15 +1,26 @@
-use wit_bindgen_core::{Files, wit_parser::Resolve};
-use wit_bindgen_scala::Opts;
+use wit_bindgen_core::{
+    Files,
+    wit_parser::{Resolve, Type, TypeDef, TypeOwner},
+};
+use wit_bindgen_scala::{Opts, TestFramework};
 
 fn generate_scala(wit: &str) -> Files {
+    generate_scala_with(
+        wit,
+        Opts {
+            base_package: "com.example.test".to_string(),
+            binding_root: None,
+            ..Default::default()
+        },
+    )
+}
+
+/// Generate the bindings of the default world of `wit` with the given options.
+fn generate_scala_with(wit: &str, opts: Opts) -> Files {
     let mut resolve = Resolve::default();
     let pkg = resolve.push_str("test.wit", wit).unwrap();
     let world = resolve.select_world(&[pkg], None).unwrap();
 
-    let opts = Opts {
-        base_package: "com.example.test".to_string(),
-        binding_root: None,
-    };
     let mut generator = opts.build();
     let mut files = Files::default();
 
@@ -80,6 +91,12 @@ fn test_variants() {
     assert!(scala_content.contains("sealed trait Outcome"));
     assert!(scala_content.contains("sealed trait Color"));
     assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitVariant"));
+
+    // Variants are closed, so every trait is sealed and every case is final
+    assert!(!scala_content.lines().any(|line| line.trim_start().starts_with("trait ")));
+    assert!(scala_content.contains("final case class Ok(value: String) extends Outcome"));
+    assert!(scala_content.contains("final case class Err(value: String) extends Outcome"));
+    assert!(scala_content.contains("case object Red extends Color"));
 }
 
 #[test]
@@ -106,20 +123,73 @@ fn test_lists_and_options() {
 }
 
 #[test]
-fn test_resources() {
+fn test_nested_lists_and_options() {
     let wit = r#"
-        package test:resources;
+        package test:nesting;
 
-        interface counters {
-            resource counter {
-                constructor(initial: s32);
-                increment: func();
-                value: func() -> s32;
+        interface shapes {
+            record point {
+                x: s32,
+                y: s32,
             }
         }
 
+        interface data {
+            use shapes.{point};
+
+            type maybe-points = option<list<point>>;
+
+            optional-list: func(items: option<list<u32>>) -> list<option<string>>;
+            nested-points: func(points: list<option<point>>) -> maybe-points;
+        }
+
         world test {
-            import counters;
+            import data;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let (_, bytes) = files
+        .iter()
+        .find(|(name, _)| name.ends_with("/data.scala"))
+        .unwrap();
+    let scala_content = std::str::from_utf8(bytes).unwrap();
+
+    assert!(scala_content.contains(
+        "def optionalList(items: java.util.Optional[Array[scala.scalajs.wit.unsigned.UInt]]): Array[java.util.Optional[String]]"
+    ));
+    assert!(scala_content.contains(
+        "type MaybePoints = java.util.Optional[Array[com.example.test.test.nesting.shapes.Point]]"
+    ));
+    assert!(scala_content.contains(
+        "def nestedPoints(points: Array[java.util.Optional[com.example.test.test.nesting.shapes.Point]]): java.util.Optional[Array[com.example.test.test.nesting.shapes.Point]]"
+    ));
+}
+
+#[test]
+fn test_since_tags() {
+    let wit = r#"
+        package test:since@0.2.0;
+
+        interface clocks {
+            /// Current time in nanoseconds.
+            @since(version = 0.2.0)
+            now: func() -> u64;
+
+            @since(version = 0.2.0)
+            record instant {
+                nanos: u64,
+            }
+
+            @since(version = 0.2.0)
+            resource timer {
+                @since(version = 0.2.0)
+                elapsed: func() -> u64;
+            }
+        }
+
+        world test {
+            import clocks;
         }
     "#;
 
@@ -127,84 +197,4571 @@ fn test_resources() {
     let contents: Vec<_> = files.iter().collect();
     let scala_content = std::str::from_utf8(contents[0].1).unwrap();
 
-    assert!(scala_content.contains("trait Counter"));
-    assert!(scala_content.contains("object Counter"));
-    assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitResourceImport"));
-    assert!(
-        scala_content.contains("@scala.scalajs.wit.annotation.WitResourceConstructor")
-    );
-    assert!(scala_content.contains("def apply(initial: Int): Counter"));
+    assert!(scala_content.contains("/** Current time in nanoseconds.\n   *\n   *  @since 0.2.0\n   */\n  @scala.scalajs.wit.annotation.WitImport"));
+    assert!(scala_content.contains("/** @since 0.2.0\n   */\n  @scala.scalajs.wit.annotation.WitRecord"));
+    assert!(scala_content.contains("/** @since 0.2.0\n   */\n  @scala.scalajs.wit.annotation.WitResourceImport"));
+    assert!(scala_content.contains("/** @since 0.2.0\n     */\n    @scala.scalajs.wit.annotation.WitResourceMethod(\"elapsed\")"));
 }
 
 #[test]
-fn test_import_export() {
+fn test_result_return_docs() {
     let wit = r#"
-        package test:both;
+        package test:results;
 
-        interface math {
-            add: func(a: s32, b: s32) -> s32;
+        interface files {
+            enum error-code {
+                not-found,
+            }
+
+            /// Opens a file.
+            open-file: func(path: string) -> result<u32, error-code>;
+            remove: func(path: string) -> result;
+            size: func(path: string) -> u64;
         }
 
         world test {
-            import math;
-            export math;
+            import files;
         }
     "#;
 
     let files = generate_scala(wit);
     let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
 
-    // Should generate 2 files: one for import, one for export
-    assert_eq!(contents.len(), 2);
+    assert!(scala_content.contains(
+        "/** Opens a file.\n   *\n   *  @return a `Result` with `ErrorCode` as its error type\n   */\n  @scala.scalajs.wit.annotation.WitImport(\"test:results/files\", \"open-file\")"
+    ));
+    assert!(scala_content.contains(
+        "/** @return a `Result` without an error payload\n   */\n  @scala.scalajs.wit.annotation.WitImport(\"test:results/files\", \"remove\")"
+    ));
+    assert_eq!(scala_content.matches("@return").count(), 2);
+}
 
-    let import_file = contents
+#[test]
+fn test_result_type_either() {
+    use wit_bindgen_scala::ResultType;
+
+    let wit = r#"
+        package test:io;
+
+        interface streams {
+            enum stream-error {
+                closed,
+            }
+
+            type read-result = result<list<u64>, stream-error>;
+
+            read: func(len: u32) -> result<list<u64>, stream-error>;
+            flush: func() -> result<_, stream-error>;
+            check: func() -> result<u32>;
+        }
+
+        world test {
+            import streams;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        result_type: ResultType::Either,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    // The error type comes first, as the `Left` of the `Either`
+    assert!(scala_content.contains(
+        "type ReadResult = scala.util.Either[StreamError, Array[scala.scalajs.wit.unsigned.ULong]]"
+    ));
+    assert!(scala_content.contains(
+        "def read(len: scala.scalajs.wit.unsigned.UInt): scala.util.Either[StreamError, Array[scala.scalajs.wit.unsigned.ULong]]"
+    ));
+    assert!(scala_content.contains("def flush(): scala.util.Either[StreamError, Unit]"));
+    assert!(scala_content.contains("def check(): scala.util.Either[Unit, scala.scalajs.wit.unsigned.UInt]"));
+    assert!(scala_content.contains("@return an `Either` with `StreamError` as its error type"));
+    assert!(!scala_content.contains("scala.scalajs.wit.Result"));
+}
+
+#[test]
+fn test_primitive_alias_in_signatures() {
+    let wit = r#"
+        package test:time;
+
+        interface clock {
+            type millis = u64;
+
+            sleep: func(ms: millis) -> list<millis>;
+        }
+
+        interface timer {
+            use clock.{millis};
+
+            record deadline {
+                after: millis,
+            }
+        }
+
+        world test {
+            import clock;
+            import timer;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let file = |name: &str| {
+        files
+            .iter()
+            .find(|(path, _)| path.ends_with(name))
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .unwrap()
+    };
+
+    let clock = file("clock.scala");
+    assert!(clock.contains("type Millis = scala.scalajs.wit.unsigned.ULong"));
+    assert!(clock.contains("def sleep(ms: Millis): Array[Millis] = scala.scalajs.wit.native"));
+
+    // `use`d aliases refer to the alias of the defining interface
+    let timer = file("timer.scala");
+    assert!(timer.contains("final case class Deadline(after: com.example.test.test.time.clock.Millis)"));
+}
+
+#[test]
+fn test_error_hierarchy() {
+    let wit = r#"
+        package test:fs;
+
+        interface files {
+            enum error-code {
+                not-found,
+                access,
+            }
+
+            variant io-error {
+                closed,
+                failed(string),
+            }
+
+            open: func(path: string) -> result<u32, error-code>;
+            remove: func(path: string) -> result<_, error-code>;
+            write: func(data: list<u8>) -> result<u64, io-error>;
+            name: func() -> result<string, string>;
+        }
+
+        world test {
+            import files;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        error_hierarchy: true,
+        wrap_errors: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains(
+        "  sealed trait ErrorCodeException extends RuntimeException {\n    def error: ErrorCode\n    override def getMessage: String = error.toString\n  }\n  object ErrorCodeException {\n    final case class NotFound() extends ErrorCodeException { def error: ErrorCode = ErrorCode.NotFound }\n    final case class Access() extends ErrorCodeException { def error: ErrorCode = ErrorCode.Access }\n    def apply(error: ErrorCode): ErrorCodeException = error match {\n      case ErrorCode.NotFound => NotFound()\n      case ErrorCode.Access => Access()\n    }\n  }"
+    ));
+    assert_eq!(scala_content.matches("sealed trait ErrorCodeException").count(), 1);
+    assert!(scala_content.contains(
+        "    final case class Failed(value: String) extends IoErrorException { def error: IoError = IoError.Failed(value) }"
+    ));
+    assert!(scala_content.contains("      case IoError.Failed(value) => Failed(value)\n"));
+    // Other error types keep a single exception
+    assert!(scala_content.contains(
+        "  final case class NameException(error: String) extends RuntimeException(error.toString)"
+    ));
+    // The wrappers throw the case of the error value
+    assert!(scala_content.contains("    case scala.scalajs.wit.Err(error) => throw ErrorCodeException(error)\n"));
+}
+
+#[test]
+fn test_named_result_as_variant() {
+    let wit = r#"
+        package test:res;
+
+        interface api {
+            type lookup = result<u32, string>;
+            type status = result;
+
+            find: func(key: string) -> lookup;
+            check: func() -> status;
+            count: func() -> result<u32, string>;
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        named_result_as_variant: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains(
+        "  @scala.scalajs.wit.annotation.WitVariant\n  sealed trait Lookup\n  object Lookup {\n    final case class Ok(value: scala.scalajs.wit.unsigned.UInt) extends Lookup\n    final case class Err(value: String) extends Lookup\n  }"
+    ));
+    assert!(scala_content.contains("    case object Ok extends Status\n    case object Err extends Status\n"));
+    assert!(!scala_content.contains("type Lookup ="));
+    // Named results are referenced by name, anonymous ones keep the runtime type
+    assert!(scala_content.contains("def find(key: String): Lookup = scala.scalajs.wit.native"));
+    assert!(scala_content.contains("def check(): Status = scala.scalajs.wit.native"));
+    assert!(scala_content.contains(
+        "def count(): scala.scalajs.wit.Result[scala.scalajs.wit.unsigned.UInt, String] = scala.scalajs.wit.native"
+    ));
+}
+
+#[test]
+fn test_wrap_errors() {
+    let wit = r#"
+        package test:io;
+
+        interface streams {
+            enum stream-error {
+                closed,
+            }
+
+            read: func(len: u32) -> result<list<u64>, stream-error>;
+            flush: func() -> result<_, stream-error>;
+            check: func() -> result;
+        }
+
+        world test {
+            import streams;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        wrap_errors: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    // Functions sharing an error type share its exception
+    assert_eq!(
+        scala_content
+            .matches("final case class StreamErrorException(error: StreamError) extends RuntimeException(error.toString)")
+            .count(),
+        1
+    );
+    // The raw function is kept next to its wrapper
+    assert!(scala_content.contains(
+        "def read(len: scala.scalajs.wit.unsigned.UInt): scala.scalajs.wit.Result[Array[scala.scalajs.wit.unsigned.ULong], StreamError] = scala.scalajs.wit.native"
+    ));
+    assert!(scala_content.contains(
+        "def readOrThrow(len: scala.scalajs.wit.unsigned.UInt): Array[scala.scalajs.wit.unsigned.ULong] = read(len) match {\n    case scala.scalajs.wit.Ok(value) => value\n    case scala.scalajs.wit.Err(error) => throw StreamErrorException(error)\n  }"
+    ));
+    assert!(scala_content.contains("def flushOrThrow(): Unit = flush() match {"));
+    // Results without an error payload have nothing to throw
+    assert!(!scala_content.contains("checkOrThrow"));
+
+    assert!(!generate_scala(wit).iter().any(|(_, contents)| {
+        std::str::from_utf8(contents).unwrap().contains("OrThrow")
+    }));
+}
+
+#[test]
+fn test_export_freestanding_as_object() {
+    use wit_bindgen_scala::ExportFreestandingAs;
+
+    let wit = r#"
+        package test:app;
+
+        interface handler {
+            record request {
+                path: string,
+            }
+
+            handle: func(req: request) -> u32;
+        }
+
+        world test {
+            export handler;
+            export run: func();
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        export_freestanding_as: ExportFreestandingAs::Object,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let file = |name: &str| {
+        files
+            .iter()
+            .find(|(path, _)| path.ends_with(name))
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .unwrap()
+    };
+
+    // Types and functions share the object, and functions are stubbed
+    let handler = file("handler.scala");
+    assert!(!handler.contains("trait Handler"));
+    assert!(handler.contains("@scala.scalajs.wit.annotation.WitExportInterface\nobject Handler {"));
+    assert!(handler.contains("final case class Request(path: String)"));
+    assert!(handler.contains("def handle(req: Request): scala.scalajs.wit.unsigned.UInt = ???\n"));
+    assert_eq!(handler.matches("object Handler").count(), 1);
+
+    let world_file = file("package.scala");
+    assert!(world_file.contains("object Test {"));
+    assert!(world_file.contains("def run(): Unit = ???\n"));
+}
+
+#[test]
+fn test_root_world_name() {
+    let wit = r#"
+        package test:app;
+
+        world placeholder {
+            import ping: func() -> u32;
+            export run: func();
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+    resolve.worlds[world].name = "$root".to_string();
+
+    let generate = |default_world_name: Option<&str>| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            default_world_name: default_world_name.map(str::to_string),
+            ..Default::default()
+        };
+        let mut files = Files::default();
+        opts.build().generate(&resolve, world, &mut files).unwrap();
+        files
+            .iter()
+            .map(|(path, contents)| (path.to_string(), std::str::from_utf8(contents).unwrap().to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    let files = generate(None);
+    let (_, import) = files
         .iter()
-        .find(|(path, _)| !path.contains("exports"))
+        .find(|(path, _)| path == "com/example/test/world/package.scala")
         .unwrap();
-    let export_file = contents
+    assert!(import.starts_with("package com.example.test\n"));
+    assert!(import.contains("package object world {"));
+    let (_, export) = files
         .iter()
-        .find(|(path, _)| path.contains("exports"))
+        .find(|(path, _)| path == "com/example/test/exports/world/package.scala")
         .unwrap();
+    assert!(export.contains("trait World {"));
+    assert!(files.iter().all(|(path, _)| !path.contains("root")));
 
-    let import_content = std::str::from_utf8(import_file.1).unwrap();
-    let export_content = std::str::from_utf8(export_file.1).unwrap();
+    let files = generate(Some("app"));
+    assert!(files.iter().any(|(path, _)| path == "com/example/test/app/package.scala"));
+}
 
-    // Import should have native marker
-    assert!(import_content.contains("= scala.scalajs.wit.native"));
-    assert!(import_content.contains("@scala.scalajs.wit.annotation.WitImport"));
+#[test]
+fn test_package_segments_are_identifiers() {
+    let wit = r#"
+        package WASI:HTTP-TYPES;
 
-    // Export should be abstract (no native marker)
-    assert!(!export_content.contains("= scala.scalajs.wit.native"));
-    assert!(export_content.contains("@scala.scalajs.wit.annotation.WitExport"));
+        interface FIELDS {
+            ping: func();
+        }
+
+        world test {
+            import FIELDS;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let (path, contents) = files.iter().next().unwrap();
+    assert_eq!(path, "com/example/test/wasi/http_types/fields.scala");
+    let scala_content = std::str::from_utf8(contents).unwrap();
+    assert!(scala_content.starts_with("package com.example.test.wasi.http_types\n"));
+    assert!(scala_content.contains("package object fields {"));
+
+    // Names built outside the WIT parser may not be valid identifiers
+    let mut resolve = Resolve::default();
+    let pkg = resolve
+        .push_str("test.wit", &wit.replace("WASI:HTTP-TYPES", "acme:http"))
+        .unwrap();
+    resolve.packages[pkg].name.namespace = "1password".to_string();
+    resolve.packages[pkg].name.name = "my.http".to_string();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        ..Default::default()
+    };
+    let mut files = Files::default();
+    opts.build().generate(&resolve, world, &mut files).unwrap();
+    let (path, contents) = files.iter().next().unwrap();
+    assert_eq!(path, "com/example/test/_1password/my_http/fields.scala");
+    assert!(std::str::from_utf8(contents)
+        .unwrap()
+        .starts_with("package com.example.test._1password.my_http\n"));
 }
 
 #[test]
-fn test_flags() {
+fn test_type_annotations() {
     let wit = r#"
-        package test:perms;
+        package test:annotated;
 
-        interface permissions {
-            flags file-perms {
-                read,
-                write,
-                execute,
+        interface shapes {
+            /// A point.
+            record point {
+                x: s32,
+                y: s32,
+            }
+
+            enum color {
+                red,
             }
         }
 
         world test {
-            import permissions;
+            import shapes;
         }
     "#;
 
-    let files = generate_scala(wit);
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        type_annotation: vec![
+            "point=@io.circe.generic.JsonCodec".to_string(),
+            "point=@deprecated(\"use Vec2\", \"1.0\")".to_string(),
+        ],
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
     let contents: Vec<_> = files.iter().collect();
     let scala_content = std::str::from_utf8(contents[0].1).unwrap();
 
-    assert!(scala_content.contains("case class FilePerms"));
-    assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitFlags"));
-    assert!(scala_content.contains("val read ="));
-    assert!(scala_content.contains("val write ="));
-    assert!(scala_content.contains("val execute ="));
-    assert!(scala_content.contains("def |"));
-    assert!(scala_content.contains("def &"));
+    assert!(scala_content.contains(
+        "  /** A point.\n   */\n  @io.circe.generic.JsonCodec\n  @deprecated(\"use Vec2\", \"1.0\")\n  @scala.scalajs.wit.annotation.WitRecord\n  final case class Point("
+    ));
+    // Other types are left alone
+    assert!(scala_content.contains("\n  @scala.scalajs.wit.annotation.WitVariant\n  sealed trait Color"));
+    assert_eq!(scala_content.matches("JsonCodec").count(), 1);
+}
+
+#[test]
+fn test_max_line_length() {
+    let wit = r#"
+        package test:wide;
+
+        interface api {
+            configure: func(a: u8, b: u16, c: u32, d: u64, e: list<u8>, f: result<u32, string>, g: string, h: bool) -> u32;
+            ping: func();
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let generate = |max_line_length| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            max_line_length: Some(max_line_length),
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let (_, contents) = files.iter().next().unwrap();
+        std::str::from_utf8(contents).unwrap().to_string()
+    };
+
+    let scala_content = generate(100);
+    assert!(scala_content.contains(
+        "  def configure(\n    a: scala.scalajs.wit.unsigned.UByte,\n    b: scala.scalajs.wit.unsigned.UShort,\n    c: scala.scalajs.wit.unsigned.UInt,\n    d: scala.scalajs.wit.unsigned.ULong,\n    e: Array[scala.scalajs.wit.unsigned.UByte],\n    f: scala.scalajs.wit.Result[scala.scalajs.wit.unsigned.UInt, String],\n    g: String,\n    h: Boolean\n  ): scala.scalajs.wit.unsigned.UInt = scala.scalajs.wit.native\n"
+    ));
+    assert!(scala_content.contains("  def ping(): Unit = scala.scalajs.wit.native\n"));
+
+    // Below the limit, the signature stays on one line
+    let scala_content = generate(1000);
+    assert!(scala_content.contains("  def configure(a: scala.scalajs.wit.unsigned.UByte, b: "));
+}
+
+#[test]
+fn test_max_line_length_flags() {
+    let wit = r#"
+        package test:wide;
+
+        interface api {
+            flags permissions {
+                read-access,
+                write-access,
+                execute-access,
+            }
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        max_line_length: Some(60),
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let (_, contents) = files.iter().next().unwrap();
+    let scala_content = std::str::from_utf8(contents).unwrap();
+
+    // Calls in the body of a method without parameters are left alone
+    assert!(scala_content.contains(
+        "    def iterator: Iterator[Permissions] = Iterator(Permissions.readAccess, Permissions.writeAccess, Permissions.executeAccess).filter(flag => contains(flag))\n"
+    ));
+    assert!(scala_content.contains("    def unary_~ : Permissions = Permissions(~value)\n"));
+    assert!(scala_content.contains("    def contains(\n      other: Permissions\n    ): Boolean"));
+}
+
+#[test]
+fn test_debug_log() {
+    let wit = r#"
+        package test:time;
+
+        interface clock {
+            record instant {
+                ms: u64,
+            }
+        }
+
+        interface timer {
+            use clock.{instant};
+
+            wait: func(until: instant) -> instant;
+        }
+
+        world test {
+            import timer;
+        }
+    "#;
+
+    let generate = |debug| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            debug,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        files
+            .iter()
+            .find(|(path, _)| *path == "debug.log")
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+    };
+
+    let log = generate(true).unwrap();
+    assert!(log.contains("namespace interface=timer namespace=test:time/timer package=com.example.test.test.time\n"));
+    assert_eq!(
+        log.matches("qualified-type type=instant qualified=com.example.test.test.time.clock.Instant\n").count(),
+        1
+    );
+    assert!(log.contains("file path=com/example/test/test/time/timer.scala owner=test:time/timer\n"));
+    assert!(log.ends_with("summary scala-files=2 imports=2 exports=0\n"));
+
+    assert!(generate(false).is_none());
+}
+
+#[test]
+fn test_debug_log_summary_counts_every_scala_file() {
+    let wit = r#"
+        package test:files;
+
+        interface api {
+            resource reader {
+                read: func() -> u32;
+            }
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        debug: true,
+        module_per_resource: true,
+        emit_runtime_stub: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let (_, contents) = files.iter().find(|(path, _)| *path == "debug.log").unwrap();
+    let log = std::str::from_utf8(contents).unwrap();
+
+    // The resource module and the runtime stub are counted with the interface file
+    assert!(log.ends_with("summary scala-files=3 imports=1 exports=0\n"));
+}
+
+#[test]
+fn test_function_section_counts() {
+    let wit = r#"
+        package test:sections;
+
+        interface api {
+            ping: func();
+            pong: func();
+
+            @unstable(feature = fancy)
+            sparkle: func();
+
+            reset: func();
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let mut resolve = Resolve {
+        all_features: true,
+        ..Default::default()
+    };
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+    let mut files = Files::default();
+    Opts {
+        base_package: "com.example.test".to_string(),
+        ..Default::default()
+    }
+    .build()
+    .generate(&resolve, world, &mut files)
+    .unwrap();
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    let stable = scala_content.find("  // Functions (3)\n").unwrap();
+    let unstable = scala_content
+        .find("  // Unstable functions: feature `fancy` (1)\n")
+        .unwrap();
+    assert!(stable < unstable);
+    assert!(scala_content[unstable..].contains("def sparkle(): Unit"));
+    assert!(!scala_content[unstable..].contains("def reset(): Unit"));
+}
+
+#[test]
+fn test_resources() {
+    let wit = r#"
+        package test:resources;
+
+        interface counters {
+            resource counter {
+                constructor(initial: s32);
+                increment: func();
+                value: func() -> s32;
+            }
+        }
+
+        world test {
+            import counters;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains("trait Counter extends java.lang.AutoCloseable {"));
+    assert!(scala_content.contains("object Counter"));
+    assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitResourceImport"));
+    assert!(
+        scala_content.contains("@scala.scalajs.wit.annotation.WitResourceConstructor")
+    );
+    assert!(scala_content.contains("def apply(initial: Int): Counter"));
+    assert!(scala_content.contains("def increment(): Unit = scala.scalajs.wit.native"));
+    assert!(scala_content.contains("def value(): Int = scala.scalajs.wit.native"));
+}
+
+#[test]
+fn test_borrowed_parameters_accept_owned_handles() {
+    let wit = r#"
+        package test:borrows;
+
+        interface nodes {
+            resource node {
+                constructor();
+                adopt: func(child: borrow<node>, other: own<node>);
+                merge: static func(a: borrow<node>, b: borrow<node>) -> node;
+            }
+        }
+
+        world test {
+            import nodes;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    // Borrows and owned handles share the resource trait, so an owned `Node`
+    // is passed to a borrowing parameter directly and needs no overload
+    assert!(scala_content.contains("def adopt(child: Node, other: Node): Unit = scala.scalajs.wit.native"));
+    assert!(scala_content.contains("def merge(a: Node, b: Node): Node = scala.scalajs.wit.native"));
+    assert_eq!(scala_content.matches("def adopt(").count(), 1);
+    assert!(!scala_content.contains("Borrow["));
+}
+
+#[test]
+fn test_record_fields_with_borrowed_handles() {
+    let wit = r#"
+        package test:conn;
+
+        interface streams {
+            resource input-stream;
+        }
+
+        interface net {
+            use streams.{input-stream};
+
+            resource socket;
+
+            record conn {
+                input: borrow<input-stream>,
+                peer: borrow<socket>,
+                owned: own<socket>,
+            }
+
+            attach: func(c: conn);
+        }
+
+        world test {
+            import net;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let (_, contents) = files
+        .iter()
+        .find(|(path, _)| path.ends_with("net.scala"))
+        .unwrap();
+    let scala_content = std::str::from_utf8(contents).unwrap();
+
+    // Borrowed fields use the resource trait like owned ones, qualified when
+    // the resource comes from another interface
+    assert!(scala_content.contains(
+        "final case class Conn(input: com.example.test.test.conn.streams.InputStream, peer: Socket, owned: Socket)"
+    ));
+}
+
+#[test]
+fn test_resource_suffix() {
+    let wit = r#"
+        package test:decorated;
+
+        interface streams {
+            resource input-stream {
+                constructor();
+                fork: func() -> input-stream;
+            }
+        }
+
+        interface pipes {
+            use streams.{input-stream};
+
+            connect: func(source: borrow<input-stream>) -> input-stream;
+        }
+
+        world test {
+            import pipes;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        resource_prefix: Some("Wit".to_string()),
+        resource_suffix: Some("Resource".to_string()),
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let file = |name: &str| {
+        files
+            .iter()
+            .find(|(path, _)| path.ends_with(name))
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .unwrap()
+    };
+
+    let streams = file("streams.scala");
+    assert!(streams.contains("trait WitInputStreamResource extends java.lang.AutoCloseable {"));
+    assert!(streams.contains("def fork(): WitInputStreamResource = scala.scalajs.wit.native"));
+    assert!(streams.contains("object WitInputStreamResource {"));
+    assert!(streams.contains("def apply(): WitInputStreamResource = scala.scalajs.wit.native"));
+    assert!(streams.contains("@scala.scalajs.wit.annotation.WitResourceImport(\"test:decorated/streams\", \"input-stream\")"));
+
+    let pipes = file("pipes.scala");
+    assert!(pipes.contains(
+        "def connect(source: com.example.test.test.decorated.streams.WitInputStreamResource): com.example.test.test.decorated.streams.WitInputStreamResource"
+    ));
+}
+
+#[test]
+fn test_no_annotations() {
+    let wit = r#"
+        package test:plain;
+
+        interface api {
+            record point {
+                x: s32,
+            }
+
+            flags perms {
+                read,
+            }
+
+            resource counter {
+                constructor(initial: s32);
+                value: func() -> s32;
+            }
+
+            origin: func() -> point;
+        }
+
+        world test {
+            import api;
+            export api;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        no_annotations: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    assert_eq!(files.iter().count(), 2);
+    for (_path, contents) in files.iter() {
+        let scala_content = std::str::from_utf8(contents).unwrap();
+        assert!(!scala_content.contains("@scala.scalajs.wit"));
+        assert!(!scala_content.contains("scala.scalajs.wit.native"));
+    }
+
+    let import = files.iter().find(|(path, _)| !path.contains("exports")).unwrap().1;
+    let import = std::str::from_utf8(import).unwrap();
+    assert!(import.contains("  final case class Point(x: Int)\n"));
+    assert!(import.contains("  def origin(): Point = ???\n"));
+    assert!(import.contains("    def value(): Int = ???\n"));
+    assert!(import.contains("    def apply(initial: Int): Counter = ???\n"));
+}
+
+#[test]
+fn test_cross_interface_resource_handles() {
+    let wit = r#"
+        package test:handles;
+
+        interface things {
+            resource thing {
+                name: func() -> string;
+            }
+        }
+
+        interface inspector {
+            use things.{thing};
+
+            inspect: func(t: borrow<thing>) -> string;
+        }
+
+        world imports {
+            import inspector;
+        }
+
+        world exports {
+            export things;
+            export inspector;
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let generate = |world: &str| {
+        let world = resolve.select_world(&[pkg], Some(world)).unwrap();
+        let mut files = Files::default();
+        Opts {
+            base_package: "com.example.test".to_string(),
+            ..Default::default()
+        }
+        .build()
+        .generate(&resolve, world, &mut files)
+        .unwrap();
+        files
+            .iter()
+            .find(|(path, _)| path.ends_with("inspector.scala"))
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .unwrap()
+    };
+
+    // Imported handles resolve to the package object of the defining interface
+    let imported = generate("imports");
+    assert!(imported.contains("def inspect(t: com.example.test.test.handles.things.Thing): String"));
+
+    // Exported handles resolve to the companion object of the exported interface
+    let exported = generate("exports");
+    assert!(exported.contains("def inspect(t: com.example.test.exports.test.handles.Things.Thing): String"));
+    assert!(exported.contains("type Thing = com.example.test.exports.test.handles.Things.Thing"));
+}
+
+#[test]
+fn test_optional_resource_handles() {
+    // WIT doesn't allow borrows in results, so the borrow is a parameter
+    let wit = r#"
+        package test:optional;
+
+        interface things {
+            resource thing {
+                peer: func() -> option<thing>;
+            }
+        }
+
+        interface finder {
+            use things.{thing};
+
+            find: func(name: string) -> option<own<thing>>;
+            compare: func(a: option<borrow<thing>>) -> bool;
+        }
+
+        world test {
+            import finder;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let file = |name: &str| {
+        files
+            .iter()
+            .find(|(path, _)| path.ends_with(name))
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .unwrap()
+    };
+
+    let finder = file("finder.scala");
+    assert!(finder.contains(
+        "def find(name: String): java.util.Optional[com.example.test.test.optional.things.Thing] = scala.scalajs.wit.native"
+    ));
+    assert!(finder.contains(
+        "def compare(a: java.util.Optional[com.example.test.test.optional.things.Thing]): Boolean = scala.scalajs.wit.native"
+    ));
+
+    let things = file("things.scala");
+    assert!(things.contains("def peer(): java.util.Optional[Thing] = scala.scalajs.wit.native"));
+}
+
+#[test]
+fn test_getter_style() {
+    use wit_bindgen_scala::GetterStyle;
+
+    let wit = r#"
+        package test:getters;
+
+        interface api {
+            resource counter {
+                value: func() -> s32;
+                get-limit: func() -> s32;
+                add: func(n: s32) -> s32;
+                reset: func();
+                zero: static func() -> counter;
+            }
+
+            version: func() -> string;
+            ping: func();
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let generate = |getter_style| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            getter_style,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let (_, contents) = files.iter().next().unwrap();
+        std::str::from_utf8(contents).unwrap().to_string()
+    };
+
+    let scala_content = generate(GetterStyle::JavaBean);
+    assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitResourceMethod(\"value\")\n    def getValue(): Int"));
+    assert!(scala_content.contains("def getLimit(): Int"));
+    assert!(scala_content.contains("def getVersion(): String"));
+    // Only methods without arguments returning a value are getters
+    assert!(scala_content.contains("def add(n: Int): Int"));
+    assert!(scala_content.contains("def reset(): Unit"));
+    assert!(scala_content.contains("def ping(): Unit"));
+    assert!(scala_content.contains("def zero(): Counter"));
+
+    let scala_content = generate(GetterStyle::Plain);
+    assert!(scala_content.contains("def value(): Int"));
+    assert!(scala_content.contains("def version(): String"));
+    assert!(!scala_content.contains("getValue"));
+}
+
+#[test]
+fn test_resource_method_name_style() {
+    use wit_bindgen_scala::ResourceMethodNameStyle;
+
+    let wit = r#"
+        package test:styles;
+
+        interface streams {
+            resource input-stream {
+                read: func(len: u64) -> list<u8>;
+                merge: static func(a: input-stream, b: input-stream) -> input-stream;
+            }
+        }
+
+        world test {
+            import streams;
+        }
+    "#;
+
+    let generate = |resource_method_name_style| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            resource_method_name_style,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let (_, bytes) = files.iter().next().unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let bare = generate(ResourceMethodNameStyle::Bare);
+    assert!(bare.contains("@scala.scalajs.wit.annotation.WitResourceMethod(\"read\")\n    def read("));
+    assert!(bare.contains("@scala.scalajs.wit.annotation.WitResourceStaticMethod(\"merge\")\n    def merge("));
+
+    let qualified = generate(ResourceMethodNameStyle::Qualified);
+    assert!(qualified.contains(
+        "@scala.scalajs.wit.annotation.WitResourceMethod(\"[method]input-stream.read\")\n    def read("
+    ));
+    assert!(qualified.contains(
+        "@scala.scalajs.wit.annotation.WitResourceStaticMethod(\"[static]input-stream.merge\")\n    def merge("
+    ));
+}
+
+#[test]
+fn test_explicit_unit_results() {
+    let wit = r#"
+        package test:units;
+
+        interface jobs {
+            resource job {
+                constructor();
+                cancel: func();
+                reset: static func();
+            }
+
+            run: func();
+        }
+
+        world test {
+            import jobs;
+            export jobs;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let file = |exports: bool| {
+        files
+            .iter()
+            .find(|(path, _)| path.contains("/exports/") == exports)
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .unwrap()
+    };
+
+    // Procedure syntax is deprecated, so functions without a result always
+    // declare `: Unit`, whether imported or exported
+    let import = file(false);
+    assert!(import.contains("def run(): Unit = scala.scalajs.wit.native"));
+    assert!(import.contains("def cancel(): Unit = scala.scalajs.wit.native"));
+    assert!(import.contains("def reset(): Unit = scala.scalajs.wit.native"));
+
+    let export = file(true);
+    assert!(export.contains("  def run(): Unit\n"));
+    assert!(export.contains("    def cancel(): Unit\n"));
+    assert!(export.contains("    def reset(): Unit\n"));
+}
+
+#[test]
+fn test_import_export() {
+    let wit = r#"
+        package test:both;
+
+        interface math {
+            add: func(a: s32, b: s32) -> s32;
+        }
+
+        world test {
+            import math;
+            export math;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+
+    // Should generate 2 files: one for import, one for export
+    assert_eq!(contents.len(), 2);
+
+    let import_file = contents
+        .iter()
+        .find(|(path, _)| !path.contains("exports"))
+        .unwrap();
+    let export_file = contents
+        .iter()
+        .find(|(path, _)| path.contains("exports"))
+        .unwrap();
+
+    let import_content = std::str::from_utf8(import_file.1).unwrap();
+    let export_content = std::str::from_utf8(export_file.1).unwrap();
+
+    // Import should have native marker
+    assert!(import_content.contains("= scala.scalajs.wit.native"));
+    assert!(import_content.contains("@scala.scalajs.wit.annotation.WitImport"));
+
+    // Export should be abstract (no native marker)
+    assert!(!export_content.contains("= scala.scalajs.wit.native"));
+    assert!(export_content.contains("@scala.scalajs.wit.annotation.WitExport"));
+}
+
+#[test]
+fn test_import_export_type_references() {
+    let wit = r#"
+        package test:both;
+
+        interface math {
+            record point {
+                x: s32,
+            }
+
+            record line {
+                a: point,
+                b: point,
+            }
+
+            dist: func(l: line) -> point;
+        }
+
+        interface geo {
+            use math.{point};
+
+            record area {
+                corner: point,
+            }
+        }
+
+        interface units {
+            record meters {
+                value: f64,
+            }
+        }
+
+        interface plot {
+            use math.{point};
+            use units.{meters};
+
+            draw: func(p: point, width: meters);
+        }
+
+        world test {
+            import math;
+            export math;
+            import geo;
+            export geo;
+            export plot;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let file = |path: &str| {
+        files
+            .iter()
+            .find(|(p, _)| *p == path)
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .unwrap()
+    };
+
+    // Types of the interface itself use simple names in both files
+    let import_math = file("com/example/test/test/both/math.scala");
+    let export_math = file("com/example/test/exports/test/both/math.scala");
+    for math in [&import_math, &export_math] {
+        assert!(math.contains("final case class Line(a: Point, b: Point)"));
+        assert!(math.contains("def dist(l: Line): Point"));
+    }
+
+    // Other interfaces reference the types of the file kind they are in
+    let import_geo = file("com/example/test/test/both/geo.scala");
+    assert!(import_geo.contains("final case class Area(corner: com.example.test.test.both.math.Point)"));
+    let export_geo = file("com/example/test/exports/test/both/geo.scala");
+    assert!(export_geo.contains("final case class Area(corner: com.example.test.exports.test.both.Math.Point)"));
+
+    // Types of interfaces that are only imported come from the import package
+    let export_plot = file("com/example/test/exports/test/both/plot.scala");
+    assert!(export_plot.contains(
+        "def draw(p: com.example.test.exports.test.both.Math.Point, width: com.example.test.test.both.units.Meters): Unit"
+    ));
+}
+
+#[test]
+fn test_flags() {
+    let wit = r#"
+        package test:perms;
+
+        interface permissions {
+            flags file-perms {
+                read,
+                write,
+                execute,
+            }
+        }
+
+        world test {
+            import permissions;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains("case class FilePerms"));
+    assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitFlags"));
+    assert!(scala_content.contains("val read ="));
+    assert!(scala_content.contains("val write ="));
+    assert!(scala_content.contains("val execute ="));
+    assert!(scala_content.contains("def |"));
+    assert!(scala_content.contains("def &"));
+}
+
+#[test]
+fn test_flags_iterator() {
+    let wit = r#"
+        package test:perms;
+
+        interface permissions {
+            flags file-perms {
+                read,
+                write,
+                execute,
+            }
+        }
+
+        world test {
+            import permissions;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    // `(FilePerms.read | FilePerms.write).iterator` yields `read` and `write`
+    assert!(scala_content.contains(
+        "    def iterator: Iterator[FilePerms] = Iterator(FilePerms.read, FilePerms.write, FilePerms.execute).filter(flag => contains(flag))\n    def toSet: Set[FilePerms] = iterator.toSet\n  }"
+    ));
+}
+
+#[test]
+fn test_flags_docs() {
+    let wit = r#"
+        package test:perms;
+
+        interface permissions {
+            flags file-perms {
+                /// Allows reading.
+                read,
+                write,
+                /// Allows running the file
+                /// as a program.
+                execute,
+            }
+        }
+
+        world test {
+            import permissions;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains(
+        "  object FilePerms {\n    /** Allows reading.\n     */\n    val read = FilePerms(1 << 0)\n    val write = FilePerms(1 << 1)\n    /** Allows running the file\n     *  as a program.\n     */\n    val execute = FilePerms(1 << 2)\n"
+    ));
+}
+
+#[test]
+fn test_flags_accessors() {
+    let wit = r#"
+        package test:perms;
+
+        interface permissions {
+            flags file-perms {
+                read,
+                write,
+                %type,
+            }
+        }
+
+        world test {
+            import permissions;
+        }
+    "#;
+
+    let generate = |flags_accessors| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            flags_accessors,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let (_, contents) = files.iter().next().unwrap();
+        std::str::from_utf8(contents).unwrap().to_string()
+    };
+
+    let scala_content = generate(true);
+    assert!(scala_content.contains(
+        "    def hasRead: Boolean = contains(FilePerms.read)\n    def hasWrite: Boolean = contains(FilePerms.write)\n    def hasType: Boolean = contains(FilePerms.`type`)\n  }"
+    ));
+
+    assert!(!generate(false).contains("def has"));
+}
+
+#[test]
+fn test_empty_flags() {
+    let wit = r#"
+        package test:perms;
+
+        interface perms {
+            flags no-permissions {}
+
+            check: func(p: no-permissions) -> bool;
+        }
+
+        world test {
+            import perms;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitFlags(0)\n  final case class NoPermissions(value: Int) {"));
+    assert!(!scala_content.contains("object NoPermissions"));
+    assert!(scala_content.contains("def check(p: NoPermissions): Boolean"));
+    assert_eq!(scala_content.matches('{').count(), scala_content.matches('}').count());
+}
+
+#[test]
+fn test_interface_named_like_a_keyword() {
+    let wit = r#"
+        package test:keywords;
+
+        interface %package {
+            record info {
+                name: string,
+            }
+        }
+
+        interface object {
+            use %package.{info};
+
+            describe: func(i: info) -> string;
+        }
+
+        world test {
+            import %package;
+            import object;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let file = |path: &str| {
+        let (_, bytes) = files.iter().find(|(name, _)| *name == path).unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let package = file("com/example/test/test/keywords/package_.scala");
+    assert!(package.contains("package object package_ {"));
+
+    let object = file("com/example/test/test/keywords/object_.scala");
+    assert!(object.contains("package object object_ {"));
+    assert!(object.contains("type Info = com.example.test.test.keywords.package_.Info"));
+}
+
+#[test]
+fn test_world_level_functions() {
+    let wit = r#"
+        package test:app@1.0.0;
+
+        world test {
+            import log: func(msg: string);
+            export run: func() -> s32;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+
+    let import_file = contents
+        .iter()
+        .find(|(path, _)| !path.contains("exports"))
+        .unwrap();
+    let export_file = contents
+        .iter()
+        .find(|(path, _)| path.contains("exports"))
+        .unwrap();
+
+    let import_content = std::str::from_utf8(import_file.1).unwrap();
+    let export_content = std::str::from_utf8(export_file.1).unwrap();
+
+    assert!(import_content.starts_with("package com.example.test\n"));
+    assert!(import_content.contains("package object test {"));
+    assert!(import_content.contains(
+        "@scala.scalajs.wit.annotation.WitImport(\"test:app/$root@1.0.0\", \"log\")"
+    ));
+    assert!(import_content.contains("def log(msg: String): Unit = scala.scalajs.wit.native"));
+
+    assert!(export_content.starts_with("package com.example.test.exports.test\n"));
+    assert!(!export_content.contains("package object"));
+    assert!(export_content.contains("@scala.scalajs.wit.annotation.WitExportInterface"));
+    assert!(export_content.contains("trait Test {"));
+    assert!(export_content.contains(
+        "@scala.scalajs.wit.annotation.WitExport(\"test:app/$root@1.0.0\", \"run\")"
+    ));
+    assert!(export_content.contains("def run(): Int"));
+}
+
+#[test]
+fn test_world_functions_returning_resources() {
+    let wit = r#"
+        package test:widgets;
+
+        interface api {
+            resource gadget;
+        }
+
+        world w {
+            use api.{gadget};
+
+            resource widget {
+                constructor();
+                name: func() -> string;
+            }
+
+            export make: func() -> own<widget>;
+            export find: func() -> option<gadget>;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let file = |path: &str| {
+        let (_, bytes) = files.iter().find(|(name, _)| *name == path).unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let imports = file("com/example/test/w/package.scala");
+    assert!(imports.contains("@scala.scalajs.wit.annotation.WitResourceImport(\"test:widgets/$root\", \"widget\")"));
+    assert!(imports.contains("trait Widget extends java.lang.AutoCloseable {"));
+    assert!(imports.contains("def name(): String = scala.scalajs.wit.native"));
+    assert!(imports.contains("def apply(): Widget = scala.scalajs.wit.native"));
+    assert!(!imports.contains("constructorWidget"));
+
+    let exports = file("com/example/test/exports/w/package.scala");
+    assert!(exports.contains("def make(): com.example.test.w.Widget\n"));
+    assert!(exports.contains("def find(): java.util.Optional[com.example.test.test.widgets.api.Gadget]\n"));
+}
+
+#[test]
+fn test_generate_all_worlds() {
+    use wit_bindgen_scala::Scala;
+
+    let wit = r#"
+        package test:multi;
+
+        interface logging {
+            log: func(msg: string);
+        }
+
+        interface metrics {
+            count: func(name: string);
+        }
+
+        world client {
+            import logging;
+            import metrics;
+        }
+
+        world server {
+            import logging;
+            export run: func();
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let client = resolve.select_world(&[pkg], Some("client")).unwrap();
+    let server = resolve.select_world(&[pkg], Some("server")).unwrap();
+
+    let generator = Scala::new(Opts {
+        base_package: "com.example.test".to_string(),
+        ..Default::default()
+    });
+    let mut files = Files::default();
+    generator.generate_all(&resolve, &[client, server], &mut files).unwrap();
+
+    let paths: Vec<&str> = files.iter().map(|(path, _)| path).collect();
+    assert_eq!(
+        paths.iter().filter(|path| **path == "com/example/test/test/multi/logging.scala").count(),
+        1
+    );
+    assert!(paths.contains(&"com/example/test/test/multi/metrics.scala"));
+    assert!(paths.contains(&"com/example/test/exports/server/package.scala"));
+
+    // The shared interface is written once, not appended to
+    let (_, logging) = files
+        .iter()
+        .find(|(path, _)| *path == "com/example/test/test/multi/logging.scala")
+        .unwrap();
+    let logging = std::str::from_utf8(logging).unwrap();
+    assert_eq!(logging.matches("package object logging {").count(), 1);
+}
+
+#[test]
+fn test_generate_all_worlds_aggregate_files() {
+    use wit_bindgen_scala::Scala;
+
+    let wit = r#"
+        package test:multi;
+
+        interface logging {
+            log: func(msg: string, level: u8);
+        }
+
+        interface metrics {
+            count: func(name: string) -> u32;
+        }
+
+        world client {
+            import logging;
+            import metrics;
+        }
+
+        world server {
+            import logging;
+            export run: func();
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let client = resolve.select_world(&[pkg], Some("client")).unwrap();
+    let server = resolve.select_world(&[pkg], Some("server")).unwrap();
+
+    let generator = Scala::new(Opts {
+        base_package: "com.example.test".to_string(),
+        emit_manifest: true,
+        emit_wit_info: true,
+        unsigned_conversions: true,
+        emit_gitattributes: true,
+        ..Default::default()
+    });
+    let mut files = Files::default();
+    generator.generate_all(&resolve, &[client, server], &mut files).unwrap();
+    let file = |path: &str| {
+        let matching: Vec<_> = files.iter().filter(|(file, _)| *file == path).collect();
+        assert_eq!(matching.len(), 1, "{}", path);
+        std::str::from_utf8(matching[0].1).unwrap().to_string()
+    };
+
+    // The manifest lists the interfaces of both worlds once
+    let manifest = file("manifest.json");
+    assert_eq!(manifest.matches("\"interface\": \"test:multi/logging\"").count(), 1);
+    assert!(manifest.contains("\"interface\": \"test:multi/metrics\""));
+
+    // The conversions cover the unsigned types of both worlds
+    let conversions = file("com/example/test/UnsignedConversions.scala");
+    assert!(conversions.contains("byteToUByte"));
+    assert!(conversions.contains("intToUInt"));
+
+    file(".gitattributes");
+
+    // Each world describes itself in its own package
+    let client_info = file("com/example/test/client/wit.scala");
+    assert!(client_info.starts_with("package com.example.test.client\n"));
+    assert!(client_info.contains("val worldName: String = \"client\""));
+    let server_info = file("com/example/test/server/wit.scala");
+    assert!(server_info.contains("val worldName: String = \"server\""));
+}
+
+#[test]
+fn test_world_include() {
+    let wit = r#"
+        package test:composed;
+
+        interface logging {
+            log: func(msg: string);
+        }
+
+        interface clock {
+            now: func() -> u64;
+        }
+
+        world base {
+            import logging;
+        }
+
+        world app {
+            include base;
+            import logging;
+            import clock;
+            export logging;
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let world = resolve.select_world(&[pkg], Some("app")).unwrap();
+    let mut files = Files::default();
+    Opts {
+        base_package: "com.example.test".to_string(),
+        ..Default::default()
+    }
+    .build()
+    .generate(&resolve, world, &mut files)
+    .unwrap();
+
+    let mut paths: Vec<_> = files.iter().map(|(path, _)| path.to_string()).collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            "com/example/test/exports/test/composed/logging.scala",
+            "com/example/test/test/composed/clock.scala",
+            "com/example/test/test/composed/logging.scala",
+        ]
+    );
+}
+
+#[test]
+fn test_generate_from_wit() {
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        ..Default::default()
+    };
+    let paths = |files: &Files| files.iter().map(|(path, _)| path.to_string()).collect::<Vec<_>>();
+
+    // A single world is selected by default
+    let single = r#"
+        package test:single;
+
+        world only {
+            import log: func(msg: string);
+        }
+    "#;
+    let files = wit_bindgen_scala::generate_from_wit(single, None, &opts).unwrap();
+    assert_eq!(paths(&files), vec!["com/example/test/only/package.scala"]);
+
+    // With several worlds, one is chosen by name
+    let several = r#"
+        package test:several;
+
+        world client {
+            import log: func(msg: string);
+        }
+
+        world server {
+            import serve: func();
+        }
+    "#;
+    let files = wit_bindgen_scala::generate_from_wit(several, Some("server"), &opts).unwrap();
+    assert_eq!(paths(&files), vec!["com/example/test/server/package.scala"]);
+    assert!(wit_bindgen_scala::generate_from_wit(several, None, &opts).is_err());
+}
+
+#[test]
+fn test_file_path_collision() {
+    let wit = r#"
+        package test:collide;
+
+        interface foo {
+            a: func();
+        }
+
+        interface FOO {
+            b: func();
+        }
+
+        world test {
+            import foo;
+            import FOO;
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        binding_root: None,
+        ..Default::default()
+    };
+    let mut generator = opts.build();
+    let mut files = Files::default();
+
+    let err = generator
+        .generate(&resolve, world, &mut files)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Scala file path collision"));
+    assert!(err.contains("test:collide/foo"));
+    assert!(err.contains("test:collide/FOO"));
+}
+
+#[test]
+fn test_lists_of_resource_handles() {
+    let wit = r#"
+        package test:handles;
+
+        interface blobs {
+            resource blob {
+                size: func() -> u32;
+            }
+        }
+
+        interface store {
+            use blobs.{blob};
+
+            resource bucket {
+                put-all: func(items: list<own<blob>>);
+            }
+
+            drop-all: func(items: list<own<blob>>);
+            inspect-all: func(items: list<borrow<blob>>) -> list<blob>;
+        }
+
+        world test {
+            import store;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let store_file = contents
+        .iter()
+        .find(|(path, _)| path.ends_with("store.scala"))
+        .unwrap();
+    let scala_content = std::str::from_utf8(store_file.1).unwrap();
+
+    let blob = "com.example.test.test.handles.blobs.Blob";
+    assert!(scala_content.contains(&format!("items: Array[{blob}]): Unit")));
+    assert!(scala_content.contains(&format!(
+        "def dropAll(items: Array[{blob}]): Unit"
+    )));
+    assert!(scala_content.contains(&format!(
+        "def inspectAll(items: Array[{blob}]): Array[{blob}]"
+    )));
+}
+
+#[test]
+fn test_static_method_returning_resource_list() {
+    let wit = r#"
+        package test:state;
+
+        interface counters {
+            resource counter {
+                constructor();
+                all: static func() -> list<own<counter>>;
+            }
+        }
+
+        world test {
+            import counters;
+            export counters;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let file = |suffix: &str| {
+        files
+            .iter()
+            .find(|(path, _)| path.ends_with(suffix))
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .unwrap()
+    };
+
+    let import = file("com/example/test/test/state/counters.scala");
+    assert!(import.contains(
+        "  object Counter {\n    @scala.scalajs.wit.annotation.WitResourceConstructor"
+    ));
+    assert!(import.contains(
+        "@scala.scalajs.wit.annotation.WitResourceStaticMethod(\"all\")\n    def all(): Array[Counter] = scala.scalajs.wit.native"
+    ));
+
+    let export = file("com/example/test/exports/test/state/counters.scala");
+    assert!(export.contains("trait CounterCompanion {"));
+    assert!(export.contains("def all(): Array[Counter]\n"));
+}
+
+#[test]
+fn test_emit_wit_names() {
+    let wit = r#"
+        package test:names;
+
+        interface types {
+            record http-request {
+                request-id: u64,
+                %type: string,
+            }
+
+            enum log-level {
+                very-verbose,
+                quiet,
+            }
+        }
+
+        world test {
+            import types;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        emit_wit_names: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains("final case class HttpRequest("));
+    assert!(scala_content.contains("object HttpRequest {"));
+    assert!(scala_content.contains("val witName: String = \"http-request\""));
+    assert!(scala_content.contains(
+        "val witFieldNames: Map[String, String] = Map(\"requestId\" -> \"request-id\", \"type\" -> \"type\")"
+    ));
+    assert!(scala_content.contains("val witName: String = \"log-level\""));
+    assert!(scala_content.contains(
+        "val witCaseNames: Map[String, String] = Map(\"VeryVerbose\" -> \"very-verbose\", \"Quiet\" -> \"quiet\")"
+    ));
+}
+
+#[test]
+fn test_export_types_in_companion_object() {
+    let wit = r#"
+        package test:exported;
+
+        interface shapes {
+            record point {
+                x: s32,
+                y: s32,
+            }
+
+            translate: func(p: point) -> point;
+        }
+
+        world test {
+            export shapes;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    let trait_start = scala_content.find("trait Shapes {").unwrap();
+    let object_start = scala_content.find("object Shapes {").unwrap();
+    let record = scala_content.find("final case class Point(").unwrap();
+
+    // The record is defined in the companion object, after the trait body
+    assert!(trait_start < object_start);
+    assert!(object_start < record);
+    assert!(scala_content.contains("trait Shapes {\n  import Shapes._\n"));
+    assert!(scala_content.contains("def translate(p: Point): Point"));
+}
+
+#[test]
+fn test_prelude_lines() {
+    let wit = r#"
+        package test:prelude;
+
+        interface api {
+            record point {
+                x: s32,
+            }
+        }
+
+        world test {
+            import api;
+            import ping: func();
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        prelude: vec![
+            "import scala.scalajs.wit.given".to_string(),
+            "import scala.language.implicitConversions".to_string(),
+        ],
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    // Both the interface file and the world file get the prelude
+    assert_eq!(files.iter().count(), 2);
+    for (_path, contents) in files.iter() {
+        let scala_content = std::str::from_utf8(contents).unwrap();
+        let lines: Vec<&str> = scala_content.lines().collect();
+        assert!(lines[0].starts_with("package "));
+        assert_eq!(lines[1], "");
+        assert_eq!(lines[2], "import scala.scalajs.wit.given");
+        assert_eq!(lines[3], "import scala.language.implicitConversions");
+        assert_eq!(lines[4], "");
+    }
+}
+
+#[test]
+fn test_multiline_records() {
+    use wit_bindgen_scala::ScalaVersion;
+
+    let wit = r#"
+        package test:records;
+
+        interface shapes {
+            record box {
+                x: s32,
+                y: s32,
+                width: u32,
+                height: u32,
+                label: string,
+            }
+        }
+
+        world test {
+            import shapes;
+        }
+    "#;
+
+    let generate = |scala_version: ScalaVersion| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            multiline_records: true,
+            scala_version,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let (_, bytes) = files.iter().next().unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let scala2 = generate(ScalaVersion::Scala2);
+    assert!(scala2.contains(
+        "final case class Box(\n    x: Int,\n    y: Int,\n    width: scala.scalajs.wit.unsigned.UInt,\n    height: scala.scalajs.wit.unsigned.UInt,\n    label: String\n  )"
+    ));
+
+    let scala3 = generate(ScalaVersion::Scala3);
+    assert!(scala3.contains(
+        "final case class Box(\n    x: Int,\n    y: Int,\n    width: scala.scalajs.wit.unsigned.UInt,\n    height: scala.scalajs.wit.unsigned.UInt,\n    label: String,\n  )"
+    ));
+}
+
+#[test]
+fn test_rename_overrides() {
+    let wit = r#"
+        package test:renames;
+
+        interface events {
+            record event {
+                %type: string,
+                source: string,
+            }
+
+            enum level {
+                info,
+                warn,
+            }
+
+            read-data: func(e: event) -> level;
+        }
+
+        world test {
+            import events;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        rename: vec![
+            "event.type=typ".to_string(),
+            "level.warn=Warning".to_string(),
+            "read-data=fetch".to_string(),
+        ],
+        emit_wit_names: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let (_, bytes) = files.iter().next().unwrap();
+    let scala_content = std::str::from_utf8(bytes).unwrap();
+
+    assert!(scala_content.contains("final case class Event(typ: String, source: String)"));
+    assert!(scala_content.contains("\"typ\" -> \"type\""));
+    assert!(scala_content.contains("case object Info extends Level"));
+    assert!(scala_content.contains("case object Warning extends Level"));
+    assert!(scala_content.contains("def fetch(e: Event): Level"));
+    assert!(!scala_content.contains("`type`"));
+
+    let invalid = Opts {
+        base_package: "com.example.test".to_string(),
+        rename: vec!["event.type=not-valid".to_string()],
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid rename"));
+}
+
+#[test]
+fn test_collapse_single_field_records() {
+    let wit = r#"
+        package test:newtypes;
+
+        interface users {
+            record user-id {
+                value: string,
+            }
+
+            record user {
+                id: user-id,
+                name: string,
+            }
+        }
+
+        world test {
+            import users;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        collapse_single_field_records: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let (_, bytes) = files.iter().next().unwrap();
+    let scala_content = std::str::from_utf8(bytes).unwrap();
+
+    assert!(scala_content.contains(
+        "@scala.scalajs.wit.annotation.WitRecord\n  final case class UserId(value: String) extends AnyVal\n"
+    ));
+    assert!(scala_content.contains("final case class User(id: UserId, name: String)\n"));
+}
+
+#[test]
+fn test_record_update_helpers() {
+    let wit = r#"
+        package test:nested;
+
+        interface shapes {
+            record point {
+                x: s32,
+                y: s32,
+            }
+
+            record circle {
+                center: point,
+                radius: u32,
+                label: option<string>,
+            }
+        }
+
+        world test {
+            import shapes;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        record_update_helpers: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains(
+        "final case class Circle(center: Point, radius: scala.scalajs.wit.unsigned.UInt, label: java.util.Optional[String]) {\n    def withCenter(f: Point => Point): Circle = copy(center = f(center))\n    def withLabel(f: java.util.Optional[String] => java.util.Optional[String]): Circle = copy(label = f(label))\n  }"
+    ));
+    assert!(!scala_content.contains("withRadius"));
+    // Records without nested fields are unchanged
+    assert!(scala_content.contains("final case class Point(x: Int, y: Int)\n"));
+}
+
+#[test]
+fn test_serial_version_uid() {
+    use wit_bindgen_scala::SerialVersionUid;
+
+    let wit = r#"
+        package test:serial;
+
+        interface data {
+            record point {
+                x: s32,
+                y: s32,
+            }
+
+            record size {
+                width: u32,
+                height: u32,
+            }
+        }
+
+        world test {
+            import data;
+        }
+    "#;
+
+    let generate = |uid: SerialVersionUid| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            serial_version_uid: Some(uid),
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let (_, bytes) = files.iter().next().unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let fixed = generate(SerialVersionUid::Fixed(42));
+    assert!(fixed.contains(
+        "@scala.scalajs.wit.annotation.WitRecord\n  @SerialVersionUID(42L)\n  final case class Point(x: Int, y: Int) extends Serializable"
+    ));
+
+    // Derived UIDs are stable across runs and differ between records
+    let auto = generate(SerialVersionUid::Auto);
+    assert_eq!(auto, generate(SerialVersionUid::Auto));
+    let uids: Vec<&str> = auto
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("@SerialVersionUID("))
+        .collect();
+    assert_eq!(uids.len(), 2);
+    assert_ne!(uids[0], uids[1]);
+    assert!(auto.contains("final case class Size(width: scala.scalajs.wit.unsigned.UInt, height: scala.scalajs.wit.unsigned.UInt) extends Serializable"));
+
+    assert_eq!("auto".parse(), Ok(SerialVersionUid::Auto));
+    assert_eq!("-7".parse(), Ok(SerialVersionUid::Fixed(-7)));
+    assert!("x".parse::<SerialVersionUid>().is_err());
+}
+
+#[test]
+fn test_destructure_tuple_payloads() {
+    let wit = r#"
+        package test:payloads;
+
+        interface types {
+            variant outcome {
+                ok(tuple<u32, string>),
+                err(string),
+                none,
+            }
+        }
+
+        world test {
+            import types;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        destructure_tuple_payloads: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains(
+        "final case class Ok(_0: scala.scalajs.wit.unsigned.UInt, _1: String) extends Outcome"
+    ));
+    assert!(scala_content.contains("final case class Err(value: String) extends Outcome"));
+    assert!(scala_content.contains("case object None extends Outcome"));
+
+    // The default keeps the single `value` field
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+    assert!(scala_content.contains(
+        "final case class Ok(value: scala.scalajs.wit.Tuple2[scala.scalajs.wit.unsigned.UInt, String]) extends Outcome"
+    ));
+}
+
+#[test]
+fn test_emit_manifest() {
+    let wit = r#"
+        package test:manifest;
+
+        interface shapes {
+            record point {
+                x: s32,
+            }
+
+            enum color {
+                red,
+            }
+
+            resource canvas {
+                clear: func();
+            }
+        }
+
+        interface math {
+            add: func(a: s32, b: s32) -> s32;
+        }
+
+        world test {
+            import shapes;
+            export math;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        emit_manifest: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let manifest = files
+        .iter()
+        .find(|(path, _)| *path == "manifest.json")
+        .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+        .unwrap();
+
+    assert!(manifest.contains("\"interface\": \"test:manifest/shapes\""));
+    assert!(manifest.contains("\"package\": \"com.example.test.test.manifest\""));
+    assert!(manifest.contains("\"file\": \"com/example/test/test/manifest/shapes.scala\""));
+    assert!(manifest.contains("\"direction\": \"import\""));
+    assert!(manifest.contains("\"types\": [\"Point\", \"Color\", \"Canvas\"]"));
+
+    assert!(manifest.contains("\"interface\": \"test:manifest/math\""));
+    assert!(manifest.contains("\"package\": \"com.example.test.exports.test.manifest\""));
+    assert!(manifest.contains("\"direction\": \"export\""));
+    assert!(manifest.contains("\"types\": []"));
+
+    // Without the option, no manifest is written
+    let files = generate_scala(wit);
+    assert!(!files.iter().any(|(path, _)| path == "manifest.json"));
+}
+
+#[test]
+fn test_emit_cli_main() {
+    let wit = r#"
+        package wasi:cli@0.2.0;
+
+        interface run {
+            run: func() -> result;
+        }
+
+        world command {
+            export run;
+        }
+    "#;
+
+    let generate = |emit_cli_main| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            emit_cli_main,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        files
+            .iter()
+            .find(|(path, _)| *path == "com/example/test/Main.scala")
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+    };
+
+    let main = generate(true).unwrap();
+    assert!(main.starts_with("package com.example.test\n"));
+    assert!(main.contains(
+        "object Main {\n  /** @return a `Result` without an error payload\n   */\n  @scala.scalajs.wit.annotation.WitExport(\"wasi:cli/run@0.2.0\", \"run\")\n  def run(): scala.scalajs.wit.Result[Unit, Unit] = ???\n}"
+    ));
+
+    assert!(generate(false).is_none());
+}
+
+#[test]
+fn test_emit_wit_info() {
+    let wit = r#"
+        package test:info@1.2.0;
+
+        interface api {
+            ping: func();
+        }
+
+        world service {
+            import api;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        emit_wit_info: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let wit_info = files
+        .iter()
+        .find(|(path, _)| *path == "com/example/test/wit.scala")
+        .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+        .unwrap();
+
+    assert!(wit_info.starts_with("package com.example.test\n"));
+    assert!(wit_info.contains("object WitInfo {"));
+    assert!(wit_info.contains("val worldName: String = \"service\""));
+    assert!(wit_info.contains("val packages: Array[String] = Array(\"test:info@1.2.0\")"));
+    assert!(wit_info.contains(&format!(
+        "val generatorVersion: String = \"{}\"",
+        env!("CARGO_PKG_VERSION")
+    )));
+
+    // Without the option, no WitInfo is written
+    let files = generate_scala(wit);
+    assert!(!files.iter().any(|(path, _)| path.ends_with("wit.scala")));
+}
+
+#[test]
+fn test_anonymous_record_parameter() {
+    let wit = r#"
+        package test:anonymous;
+
+        interface canvas {
+            record point {
+                x: s32,
+                y: s32,
+            }
+
+            move-to: func(target: point);
+            line-to: func(target: point) -> option<point>;
+        }
+
+        world test {
+            import canvas;
+        }
+    "#;
+
+    // WIT syntax can't express inline records, so make the parameters use
+    // an anonymous copy of `point`
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+    let interface_id = resolve.packages[pkg].interfaces["canvas"];
+    let point = resolve.interfaces[interface_id].types["point"];
+    let anonymous = resolve.types.alloc(TypeDef {
+        name: None,
+        kind: resolve.types[point].kind.clone(),
+        owner: TypeOwner::None,
+        docs: Default::default(),
+        stability: Default::default(),
+    });
+    for func in resolve.interfaces[interface_id].functions.values_mut() {
+        func.params[0].1 = Type::Id(anonymous);
+    }
+
+    let mut files = Files::default();
+    Opts {
+        base_package: "com.example.test".to_string(),
+        ..Default::default()
+    }
+    .build()
+    .generate(&resolve, world, &mut files)
+    .unwrap();
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    let start = scala_content.find("final case class AnonymousRecord").unwrap() + "final case class ".len();
+    let end = start + scala_content[start..].find('(').unwrap();
+    let name = &scala_content[start..end];
+    assert_eq!(name.len(), "AnonymousRecord".len() + 8);
+    assert_eq!(scala_content.matches("final case class Anonymous").count(), 1);
+    assert!(scala_content.contains(&format!("final case class {}(x: Int, y: Int)", name)));
+    assert!(scala_content.contains(&format!("def moveTo(target: {}): Unit", name)));
+    assert!(scala_content.contains(&format!("def lineTo(target: {}): java.util.Optional[Point]", name)));
+}
+
+#[test]
+fn test_exclude_interface() {
+    let wit = r#"
+        package test:filter;
+
+        interface clocks {
+            now: func() -> u64;
+        }
+
+        interface api {
+            ping: func();
+        }
+
+        world test {
+            import clocks;
+            import api;
+        }
+    "#;
+
+    let generate = |include: &[&str], exclude: &[&str]| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            include_interface: include.iter().map(|s| s.to_string()).collect(),
+            exclude_interface: exclude.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        files.iter().map(|(path, _)| path.to_string()).collect::<Vec<_>>()
+    };
+
+    let api = "com/example/test/test/filter/api.scala".to_string();
+    assert_eq!(generate(&[], &["test:filter/clocks"]), vec![api.clone()]);
+    assert_eq!(generate(&["test:filter/api"], &[]), vec![api]);
+    assert!(generate(&[], &["test:filter"]).is_empty());
+}
+
+#[test]
+fn test_exclude_used_interface() {
+    let wit = r#"
+        package test:filter;
+
+        interface clocks {
+            record instant {
+                nanos: u64,
+            }
+        }
+
+        interface api {
+            use clocks.{instant};
+
+            now: func() -> instant;
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        exclude_interface: vec!["test:filter/clocks".to_string()],
+        ..Default::default()
+    };
+    let mut files = Files::default();
+    let Err(err) = opts.build().generate(&resolve, world, &mut files) else {
+        panic!("using a type of a skipped interface should fail");
+    };
+    assert_eq!(
+        err.to_string(),
+        "interface `test:filter/api` uses type `instant` from skipped interface `test:filter/clocks`"
+    );
+}
+
+#[test]
+fn test_scala_native_target() {
+    use wit_bindgen_scala::Target;
+
+    let wit = r#"
+        package test:native;
+
+        interface api {
+            record sample {
+                count: u32,
+            }
+
+            measure: func(s: sample) -> result<tuple<u8, u64>, string>;
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let generate = |target, native_marker: Option<&str>| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            target,
+            native_marker: native_marker.map(str::to_string),
+            emit_runtime_stub: true,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let file = |name: &str| {
+            files
+                .iter()
+                .find(|(path, _)| path.ends_with(name))
+                .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+                .unwrap()
+        };
+        (file("api.scala"), file("runtime_stub.scala"))
+    };
+
+    let (api, stub) = generate(Target::ScalaNative, None);
+    assert!(!api.contains("scala.scalajs"));
+    assert!(api.contains("@scala.scalanative.wit.annotation.WitRecord"));
+    assert!(api.contains("final case class Sample(count: scala.scalanative.wit.unsigned.UInt)"));
+    assert!(api.contains(
+        "def measure(s: Sample): scala.scalanative.wit.Result[scala.scalanative.wit.Tuple2[scala.scalanative.wit.unsigned.UByte, scala.scalanative.wit.unsigned.ULong], String] = scala.scalanative.wit.native"
+    ));
+    assert!(stub.contains("package scala.scalanative {\n  package object wit {"));
+    assert!(stub.contains("package scala.scalanative.wit {"));
+
+    // The marker can be overridden on its own
+    let (api, _) = generate(Target::ScalaNative, Some("extern"));
+    assert!(api.contains("): scala.scalanative.wit.Result["));
+    assert!(api.contains("String] = extern\n"));
+
+    // The default target keeps the Scala.js runtime
+    let (api, _) = generate(Target::ScalaJs, None);
+    assert!(api.contains("@scala.scalajs.wit.annotation.WitRecord"));
+}
+
+#[test]
+fn test_runtime_rewrites_keep_docs() {
+    use wit_bindgen_scala::Target;
+
+    let wit = r#"
+        package test:docs;
+
+        interface api {
+            /// Implemented by `scala.scalajs.wit.native`, like
+            /// @scala.scalajs.wit.annotation.WitImport members
+            ping: func(n: u32);
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let docs = "  /** Implemented by `scala.scalajs.wit.native`, like\n   *  @scala.scalajs.wit.annotation.WitImport members\n   */\n";
+    let generate = |opts: Opts| {
+        let files = generate_scala_with(wit, opts);
+        let (_, bytes) = files.iter().next().unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let relocated = generate(Opts {
+        base_package: "com.example.test".to_string(),
+        target: Target::ScalaNative,
+        ..Default::default()
+    });
+    assert!(relocated.contains(&format!(
+        "{}  @scala.scalanative.wit.annotation.WitImport(\"test:docs/api\", \"ping\")\n  def ping(n: scala.scalanative.wit.unsigned.UInt): Unit = scala.scalanative.wit.native\n",
+        docs
+    )));
+
+    let stripped = generate(Opts {
+        base_package: "com.example.test".to_string(),
+        no_annotations: true,
+        ..Default::default()
+    });
+    assert!(stripped.contains(&format!("{}  def ping(n: scala.scalajs.wit.unsigned.UInt): Unit = ???\n", docs)));
+}
+
+#[test]
+fn test_emit_runtime_stub() {
+    let wit = r#"
+        package test:stub;
+
+        interface api {
+            ping: func(n: u32) -> result<u32, string>;
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        emit_runtime_stub: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let stub = files
+        .iter()
+        .find(|(path, _)| *path == "runtime_stub.scala")
+        .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+        .unwrap();
+
+    assert!(stub.contains("package scala.scalajs.wit {"));
+    assert!(stub.contains("  sealed trait Result[+T, +E]\n"));
+    assert!(stub.contains("  final case class Tuple2[T1, T2](_1: T1, _2: T2)\n"));
+    assert!(stub.contains("  final case class Tuple22["));
+    assert!(!stub.contains("Tuple23"));
+    assert!(stub.contains("  final class OptionArray[T](val defined: Array[Boolean], val values: Array[T])\n"));
+    assert!(stub.contains("  final class Lease[T](val value: T)\n"));
+    assert!(stub.contains("    final class UInt(val value: Int) extends AnyVal\n"));
+    assert!(stub.contains("    def native: Nothing = "));
+    for annotation in [
+        "WitImport(module: String, name: String)",
+        "WitExport(module: String, name: String)",
+        "WitExportInterface",
+        "WitRecord",
+        "WitVariant",
+        "WitFlags(numFlags: Int)",
+        "WitResourceImport(module: String, name: String)",
+        "WitResourceExport(module: String, name: String)",
+        "WitResourceConstructor",
+        "WitResourceMethod(name: String)",
+        "WitResourceStaticMethod(name: String)",
+        "WitResourceDrop",
+    ] {
+        assert!(stub.contains(&format!(
+            "    final class {} extends scala.annotation.StaticAnnotation\n",
+            annotation
+        )));
+    }
+
+    // Without the option, no stub is written
+    let files = generate_scala(wit);
+    assert!(!files.iter().any(|(path, _)| path == "runtime_stub.scala"));
+}
+
+#[test]
+fn test_deeply_nested_lists() {
+    let deep = format!("{}s32{}", "list<".repeat(50), ">".repeat(50));
+    let wit = format!(
+        r#"
+        package test:deep;
+
+        interface api {{
+            flatten: func(values: {}) -> s32;
+        }}
+
+        world test {{
+            import api;
+        }}
+    "#,
+        deep
+    );
+
+    let files = generate_scala(&wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+    let rendered = format!("{}Int{}", "Array[".repeat(50), "]".repeat(50));
+    assert!(scala_content.contains(&format!("def flatten(values: {}): Int", rendered)));
+
+    // Beyond the configured depth, generation fails instead of overflowing
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", &wit).unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        max_type_depth: Some(10),
+        ..Default::default()
+    };
+    let mut files = Files::default();
+    let Err(err) = opts.build().generate(&resolve, world, &mut files) else {
+        panic!("types nested deeper than the limit should fail");
+    };
+    assert_eq!(
+        err.to_string(),
+        "failed to generate `com/example/test/test/deep/api.scala`: type nesting exceeds the maximum depth of 10 (see `--max-type-depth`)"
+    );
+}
+
+#[test]
+fn test_nested_results() {
+    let wit = r#"
+        package test:nested;
+
+        interface errs {
+            enum e1 {
+                a,
+            }
+
+            enum e2 {
+                b,
+            }
+        }
+
+        interface api {
+            use errs.{e1, e2};
+
+            nested: func() -> result<result<u32, e1>, e2>;
+            nested-err: func() -> result<_, result<string>>;
+            optional: func() -> option<result<u32, string>>;
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let api_file = contents
+        .iter()
+        .find(|(path, _)| path.ends_with("api.scala"))
+        .unwrap();
+    let scala_content = std::str::from_utf8(api_file.1).unwrap();
+
+    assert!(scala_content.contains(
+        "def nested(): scala.scalajs.wit.Result[scala.scalajs.wit.Result[scala.scalajs.wit.unsigned.UInt, com.example.test.test.nested.errs.E1], com.example.test.test.nested.errs.E2] = scala.scalajs.wit.native"
+    ));
+    assert!(scala_content.contains(
+        "def nestedErr(): scala.scalajs.wit.Result[Unit, scala.scalajs.wit.Result[String, Unit]] = scala.scalajs.wit.native"
+    ));
+    assert!(scala_content.contains(
+        "def optional(): java.util.Optional[scala.scalajs.wit.Result[scala.scalajs.wit.unsigned.UInt, String]] = scala.scalajs.wit.native"
+    ));
+}
+
+#[test]
+fn test_generated_files_are_normalized() {
+    let wit = r#"
+        package test:sections;
+
+        interface everything {
+            record point {
+                x: s32,
+            }
+
+            resource counter {
+                constructor();
+                get: func() -> s32;
+            }
+
+            reset: func();
+        }
+
+        world test {
+            import everything;
+            import log: func(msg: string);
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    for (_path, contents) in files.iter() {
+        let scala_content = std::str::from_utf8(contents).unwrap();
+        assert!(!scala_content.contains("\n\n\n\n"));
+        assert!(scala_content.lines().all(|line| line == line.trim_end()));
+    }
+}
+
+#[test]
+fn test_export_resources() {
+    let wit = r#"
+        package test:exported;
+
+        interface counters {
+            resource counter {
+                constructor(initial: s32);
+                increment: func(by: u32);
+                get: func() -> s32;
+                merge: static func(a: borrow<counter>, b: borrow<counter>) -> counter;
+            }
+
+            make: func() -> counter;
+        }
+
+        world test {
+            export counters;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains(
+        "@scala.scalajs.wit.annotation.WitResourceExport(\"test:exported/counters\", \"counter\")"
+    ));
+    assert!(scala_content.contains("trait Counter {"));
+    assert!(scala_content.contains("trait CounterCompanion {"));
+
+    // Instance methods are abstract and take `self` implicitly
+    assert!(scala_content.contains(
+        "@scala.scalajs.wit.annotation.WitResourceMethod(\"increment\")\n    def increment(by: scala.scalajs.wit.unsigned.UInt): Unit\n"
+    ));
+    assert!(scala_content.contains("def get(): Int\n"));
+
+    // Constructor and static methods are abstract members of the companion trait
+    assert!(scala_content.contains(
+        "@scala.scalajs.wit.annotation.WitResourceConstructor\n    def apply(initial: Int): Counter\n"
+    ));
+    assert!(scala_content.contains(
+        "@scala.scalajs.wit.annotation.WitResourceStaticMethod(\"merge\")\n    def merge(a: Counter, b: Counter): Counter\n"
+    ));
+
+    assert!(!scala_content.contains("scala.scalajs.wit.native"));
+    assert!(!scala_content.contains("WitResourceDrop"));
+}
+
+#[test]
+fn test_flatten_single_variant() {
+    let wit = r#"
+        package test:newtype;
+
+        interface types {
+            variant user-id {
+                id(u64),
+            }
+
+            variant marker {
+                only,
+            }
+
+            variant outcome {
+                ok(string),
+                err(string),
+            }
+        }
+
+        world test {
+            import types;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        flatten_single_variant: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains(
+        "@scala.scalajs.wit.annotation.WitVariant\n  final case class UserId(value: scala.scalajs.wit.unsigned.ULong)\n"
+    ));
+    assert!(!scala_content.contains("sealed trait UserId"));
+
+    // Payload-less and multi-case variants keep the sealed trait hierarchy
+    assert!(scala_content.contains("sealed trait Marker"));
+    assert!(scala_content.contains("sealed trait Outcome"));
+    assert!(scala_content.contains("final case class Ok(value: String) extends Outcome"));
+}
+
+#[test]
+fn test_string_type() {
+    use wit_bindgen_scala::StringType;
+
+    let wit = r#"
+        package test:strings;
+
+        interface names {
+            record person {
+                name: string,
+                aliases: list<string>,
+            }
+
+            greet: func(name: string) -> option<string>;
+        }
+
+        world test {
+            import names;
+        }
+    "#;
+
+    let generate = |string_type: StringType| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            string_type,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let (_, bytes) = files.iter().next().unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let default = generate(StringType::String);
+    assert!(default.contains("final case class Person(name: String, aliases: Array[String])"));
+    assert!(default.contains("def greet(name: String): java.util.Optional[String]"));
+
+    let wit_string = generate(StringType::WitString);
+    assert!(wit_string.contains(
+        "final case class Person(name: scala.scalajs.wit.WitString, aliases: Array[scala.scalajs.wit.WitString])"
+    ));
+    assert!(wit_string.contains(
+        "def greet(name: scala.scalajs.wit.WitString): java.util.Optional[scala.scalajs.wit.WitString]"
+    ));
+}
+
+#[test]
+fn test_bytes_type() {
+    let wit = r#"
+        package test:bytes;
+
+        interface io {
+            type buffer = list<u8>;
+
+            write: func(data: list<u8>, flag: u8, signed: list<s8>) -> buffer;
+        }
+
+        world test {
+            import io;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        bytes_type: Some("Array[Byte]".to_string()),
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains("type Buffer = Array[Byte]"));
+    assert!(scala_content.contains(
+        "def write(data: Array[Byte], flag: scala.scalajs.wit.unsigned.UByte, signed: Array[Byte]): Array[Byte]"
+    ));
+
+    // Without the option, `list<u8>` keeps the unsigned element type
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+    assert!(scala_content.contains(
+        "def write(data: Array[scala.scalajs.wit.unsigned.UByte], flag: scala.scalajs.wit.unsigned.UByte, signed: Array[Byte]): Array[scala.scalajs.wit.unsigned.UByte]"
+    ));
+}
+
+#[test]
+fn test_char_list_as_string() {
+    let wit = r#"
+        package test:text;
+
+        interface chars {
+            echo: func(text: list<char>, sep: char, lines: list<list<char>>) -> list<char>;
+        }
+
+        world test {
+            import chars;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        char_list_as_string: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+    assert!(scala_content.contains("def echo(text: String, sep: Char, lines: Array[String]): String"));
+
+    // Without the option, `list<char>` is an array
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+    assert!(scala_content.contains(
+        "def echo(text: Array[Char], sep: Char, lines: Array[Array[Char]]): Array[Char]"
+    ));
+}
+
+#[test]
+fn test_case_named_like_its_type() {
+    let wit = r#"
+        package test:clash;
+
+        interface colors {
+            enum color {
+                color,
+                red,
+            }
+
+            variant shape {
+                shape(u32),
+                none,
+            }
+        }
+
+        world test {
+            import colors;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains("case object ColorCase extends Color"));
+    assert!(!scala_content.contains("case object Color extends Color"));
+    assert!(scala_content.contains("case object Red extends Color"));
+    assert!(scala_content.contains("final case class ShapeCase(value: scala.scalajs.wit.unsigned.UInt) extends Shape"));
+}
+
+#[test]
+fn test_enum_ordering() {
+    use wit_bindgen_scala::ScalaVersion;
+
+    let wit = r#"
+        package test:ordering;
+
+        interface colors {
+            enum color {
+                red,
+                green,
+                blue,
+            }
+        }
+
+        world test {
+            import colors;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        enum_ordering: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains(
+        "implicit val colorOrdering: Ordering[Color] = Ordering.by[Color, Int] {\n      case Red => 0\n      case Green => 1\n      case Blue => 2\n    }"
+    ));
+
+    assert_eq!(scala_content.matches("implicit val colorOrdering").count(), 1);
+
+    // Scala 3 defines the instance as a given
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        enum_ordering: true,
+        scala_version: ScalaVersion::Scala3,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let (_, bytes) = files.iter().next().unwrap();
+    let scala3_content = std::str::from_utf8(bytes).unwrap();
+    assert!(scala3_content.contains("  given colorOrdering: Ordering[Color] = Ordering.by[Color, Int] {\n      case Red => 0\n"));
+    assert!(!scala3_content.contains("implicit val"));
+
+    // Not emitted by default
+    let default_content = generate_scala(wit);
+    let (_, bytes) = default_content.iter().next().unwrap();
+    assert!(!std::str::from_utf8(bytes).unwrap().contains("Ordering"));
+}
+
+#[test]
+fn test_emit_jsexport() {
+    let wit = r#"
+        package test:js;
+
+        interface api {
+            resource session {
+                ping: func() -> u32;
+            }
+
+            greet: func(name: string) -> string;
+        }
+
+        world test {
+            export api;
+            export run: func();
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        emit_jsexport: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let file = |suffix: &str| {
+        let (_, bytes) = files.iter().find(|(name, _)| name.ends_with(suffix)).unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let interface = file("/api.scala");
+    assert!(interface.contains(
+        "@scala.scalajs.wit.annotation.WitExport(\"test:js/api\", \"greet\")\n  @scala.scalajs.js.annotation.JSExport(\"greet\")\n  def greet(name: String): String"
+    ));
+    assert!(interface.contains(
+        "@scala.scalajs.wit.annotation.WitResourceExport(\"test:js/api\", \"session\")\n  @scala.scalajs.js.annotation.JSExportAll\n  trait Session {"
+    ));
+
+    let world = file("exports/test/package.scala");
+    assert!(world.contains("@scala.scalajs.js.annotation.JSExport(\"run\")\n  def run(): Unit"));
+}
+
+#[test]
+fn test_emit_show() {
+    use wit_bindgen_scala::ScalaVersion;
+
+    let wit = r#"
+        package test:show;
+
+        interface shapes {
+            record point {
+                x: s32,
+                y: s32,
+            }
+
+            record segment {
+                start: point,
+                label: string,
+            }
+
+            enum color {
+                red,
+            }
+
+            record path {
+                points: list<point>,
+                origin: option<point>,
+            }
+
+            variant shape {
+                dot(point),
+                blank,
+            }
+
+            flags style {
+                bold,
+                italic,
+            }
+        }
+
+        world test {
+            import shapes;
+        }
+    "#;
+
+    let generate = |scala_version: ScalaVersion, show_typeclass: Option<&str>| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            emit_show: true,
+            show_typeclass: show_typeclass.map(str::to_string),
+            scala_version,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let (_, bytes) = files.iter().next().unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let scala2 = generate(ScalaVersion::Scala2, None);
+    assert!(scala2.contains(
+        "object Point {\n    implicit val pointShow: cats.Show[Point] = new cats.Show[Point] {\n      def show(value: Point): String = s\"Point(x = ${value.x}, y = ${value.y})\"\n    }\n  }"
+    ));
+    assert!(scala2.contains(
+        "s\"Segment(start = ${implicitly[cats.Show[Point]].show(value.start)}, label = ${value.label})\""
+    ));
+    assert!(scala2.contains("implicit val colorShow: cats.Show[Color] = new cats.Show[Color] {"));
+    // Lists and options show their elements with the element instances
+    assert!(scala2.contains(
+        "s\"Path(points = ${value.points.map(x0 => implicitly[cats.Show[Point]].show(x0)).mkString(\"[\", \", \", \"]\")}, origin = ${(if (value.origin.isPresent) s\"Some(${implicitly[cats.Show[Point]].show(value.origin.get)})\" else \"None\")})\""
+    ));
+    assert!(scala2.contains(
+        "def show(value: Shape): String = value match {\n        case Shape.Dot(payload) => s\"Dot(${implicitly[cats.Show[Point]].show(payload)})\"\n        case Shape.Blank => \"Blank\"\n      }"
+    ));
+    assert!(scala2.contains(
+        "def show(value: Style): String = Seq(Style.bold -> \"bold\", Style.italic -> \"italic\").collect { case (flag, flagName) if value.contains(flag) => flagName }.mkString(\"Style(\", \" | \", \")\")"
+    ));
+
+    let scala3 = generate(ScalaVersion::Scala3, Some("com.example.Show"));
+    assert!(scala3.contains("given pointShow: com.example.Show[Point] with {"));
+    assert!(scala3.contains("${summon[com.example.Show[Point]].show(value.start)}"));
+}
+
+#[test]
+fn test_emit_show_flattened_variant() {
+    let wit = r#"
+        package test:show;
+
+        interface ids {
+            variant user-id {
+                id(u64),
+            }
+
+            record user {
+                id: user-id,
+            }
+        }
+
+        world test {
+            import ids;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        emit_show: true,
+        flatten_single_variant: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let (_, bytes) = files.iter().next().unwrap();
+    let scala_content = std::str::from_utf8(bytes).unwrap();
+
+    // The record summons the instance of the flattened variant, so it must exist
+    assert!(scala_content.contains("s\"User(id = ${implicitly[cats.Show[UserId]].show(value.id)})\""));
+    assert!(scala_content.contains(
+        "final case class UserId(value: scala.scalajs.wit.unsigned.ULong)\n  object UserId {\n    implicit val userIdShow: cats.Show[UserId] = new cats.Show[UserId] {\n      def show(value: UserId): String = s\"UserId(${value.value})\"\n    }\n  }"
+    ));
+}
+
+#[test]
+fn test_emit_show_mapped_types() {
+    let wit = r#"
+        package test:show;
+
+        interface clock {
+            record datetime {
+                seconds: u64,
+            }
+        }
+
+        interface events {
+            use clock.{datetime};
+
+            record event {
+                at: datetime,
+                name: string,
+            }
+        }
+
+        world test {
+            import clock;
+            import events;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        emit_show: true,
+        type_map: vec!["test:show/clock.datetime=java.time.Instant".to_string()],
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let (_, bytes) = files.iter().find(|(path, _)| path.ends_with("/events.scala")).unwrap();
+    let scala_content = std::str::from_utf8(bytes).unwrap();
+
+    // Mapped types have no generated instance to summon
+    assert!(scala_content.contains("s\"Event(at = ${value.at}, name = ${value.name})\""));
+}
+
+#[test]
+fn test_drop_method_name() {
+    let wit = r#"
+        package test:drops;
+
+        interface handles {
+            resource handle {
+                constructor();
+            }
+        }
+
+        world test {
+            import handles;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        drop_method_name: Some("release".to_string()),
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    assert!(scala_content.contains("trait Handle extends java.lang.AutoCloseable {"));
+    assert!(scala_content.contains("def release(): Unit = scala.scalajs.wit.native"));
+    assert!(scala_content.contains("override def close(): Unit = release()"));
+}
+
+#[test]
+fn test_package_object_name() {
+    use wit_bindgen_scala::PackageObjectName;
+
+    let wit = r#"
+        package test:naming;
+
+        interface streams {
+            record chunk {
+                size: u32,
+            }
+
+            read: func() -> chunk;
+        }
+
+        interface consumer {
+            use streams.{chunk};
+
+            consume: func(c: chunk);
+        }
+
+        world test {
+            import streams;
+        }
+
+        world both {
+            import streams;
+            import consumer;
+        }
+    "#;
+
+    let generate = |strategy: PackageObjectName, world: &str| {
+        let mut resolve = Resolve::default();
+        let pkg = resolve.push_str("test.wit", wit).unwrap();
+        let world = resolve.select_world(&[pkg], Some(world)).unwrap();
+
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            package_object_name: strategy,
+            ..Default::default()
+        };
+        let mut files = Files::default();
+        opts.build().generate(&resolve, world, &mut files).map(|()| files)
+    };
+    let contents = |files: &Files| -> String {
+        let (_, bytes) = files.iter().next().unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let default = contents(&generate(PackageObjectName::Interface, "test").unwrap());
+    assert!(default.contains("package object streams {"));
+
+    let last_segment = contents(&generate(PackageObjectName::LastPackageSegment, "test").unwrap());
+    assert!(last_segment.contains("package object naming {"));
+
+    let fixed = generate(PackageObjectName::Fixed("api".to_string()), "test").unwrap();
+    assert!(contents(&fixed).contains("package object api {"));
+
+    // Two interfaces of one package can't share a package object
+    let Err(err) = generate(PackageObjectName::Fixed("api".to_string()), "both") else {
+        panic!("expected a package object collision");
+    };
+    assert!(err.to_string().contains("package object collision"));
+
+    assert_eq!("interface".parse(), Ok(PackageObjectName::Interface));
+    assert_eq!("last-package-segment".parse(), Ok(PackageObjectName::LastPackageSegment));
+    assert_eq!("fixed=api".parse(), Ok(PackageObjectName::Fixed("api".to_string())));
+    assert!("other".parse::<PackageObjectName>().is_err());
+}
+
+#[test]
+fn test_module_per_resource() {
+    let wit = r#"
+        package test:split;
+
+        interface io {
+            record options {
+                size: u32,
+            }
+
+            resource reader {
+                constructor(opts: options);
+                read: func() -> list<u8>;
+            }
+
+            resource writer {
+                write: func(data: list<u8>);
+            }
+
+            create: func() -> reader;
+        }
+
+        world test {
+            import io;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        module_per_resource: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let contents: Vec<_> = files.iter().collect();
+    assert_eq!(contents.len(), 3);
+
+    let file = |path: &str| {
+        let (_, bytes) = contents.iter().find(|(name, _)| *name == path).unwrap();
+        std::str::from_utf8(bytes).unwrap()
+    };
+
+    let interface = file("com/example/test/test/split/io.scala");
+    assert!(interface.contains("package object io {"));
+    assert!(interface.contains("final case class Options("));
+    assert!(interface.contains("def create(): Reader"));
+    assert!(!interface.contains("trait Reader"));
+
+    let reader = file("com/example/test/test/split/io/reader.scala");
+    assert!(reader.starts_with("package com.example.test.test.split.io\n"));
+    assert!(reader.contains("trait Reader extends java.lang.AutoCloseable {"));
+    assert!(reader.contains("def apply(opts: Options): Reader"));
+
+    let writer = file("com/example/test/test/split/io/writer.scala");
+    assert!(writer.contains("trait Writer extends java.lang.AutoCloseable {"));
+}
+
+#[test]
+fn test_resource_method_named_like_drop() {
+    let wit = r#"
+        package test:closing;
+
+        interface files {
+            resource file {
+                close: func();
+            }
+
+            resource socket {
+                close: func(code: u32) -> bool;
+            }
+
+            resource pipe {
+                close: func() -> u32;
+            }
+        }
+
+        world test {
+            import files;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let contents: Vec<_> = files.iter().collect();
+    let scala_content = std::str::from_utf8(contents[0].1).unwrap();
+
+    // The WIT `close()` implements `AutoCloseable` itself
+    assert!(scala_content.contains(
+        "@scala.scalajs.wit.annotation.WitResourceMethod(\"close\")\n    def close(): Unit = scala.scalajs.wit.native"
+    ));
+    assert!(scala_content.contains(
+        "@scala.scalajs.wit.annotation.WitResourceDrop\n    def _close(): Unit = scala.scalajs.wit.native\n  }"
+    ));
+
+    // An overloaded `close` still needs `AutoCloseable.close` to drop the handle
+    assert!(scala_content.contains("def close(code: scala.scalajs.wit.unsigned.UInt): Boolean = scala.scalajs.wit.native"));
+    assert!(scala_content.contains(
+        "def _close(): Unit = scala.scalajs.wit.native\n    override def close(): Unit = _close()\n  }"
+    ));
+
+    assert_eq!(scala_content.matches("def close(): Unit = scala.scalajs.wit.native").count(), 1);
+
+    // A `close()` returning a value can't override `AutoCloseable.close`
+    assert!(scala_content.contains("trait Pipe {"));
+    assert!(scala_content.contains(
+        "def close(): scala.scalajs.wit.unsigned.UInt = scala.scalajs.wit.native\n    // Dropping a handle more than once traps; call this at most once.\n    @scala.scalajs.wit.annotation.WitResourceDrop\n    def _close(): Unit = scala.scalajs.wit.native\n  }"
+    ));
+    assert!(scala_content.contains("trait File extends java.lang.AutoCloseable {"));
+    assert!(scala_content.contains("trait Socket extends java.lang.AutoCloseable {"));
+}
+
+#[test]
+fn test_shared_types() {
+    let wit = r#"
+        package test:shared;
+
+        interface geometry {
+            record point {
+                x: s32,
+                y: s32,
+            }
+
+            enum unit {
+                px,
+                em,
+            }
+
+            type coord = s32;
+        }
+
+        interface canvas {
+            use geometry.{point};
+
+            record line {
+                start: point,
+                stop: point,
+            }
+
+            draw: func(l: line, at: point);
+        }
+
+        world test {
+            import canvas;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        shared_types: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+
+    let file = |name: &str| {
+        files
+            .iter()
+            .find(|(path, _)| *path == name)
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .unwrap()
+    };
+
+    let types = file("com/example/test/test/shared/types/package.scala");
+    assert!(types.starts_with("package com.example.test.test.shared\n"));
+    assert!(types.contains("package object types {"));
+    assert!(types.contains("final case class Point(x: Int, y: Int)"));
+    assert!(types.contains("sealed trait Unit"));
+    assert!(types.contains(
+        "final case class Line(start: com.example.test.test.shared.types.Point, stop: com.example.test.test.shared.types.Point)"
+    ));
+
+    let canvas = file("com/example/test/test/shared/canvas.scala");
+    assert!(!canvas.contains("case class"));
+    assert!(canvas.contains(
+        "def draw(l: com.example.test.test.shared.types.Line, at: com.example.test.test.shared.types.Point): Unit"
+    ));
+
+    // Aliases stay in their interface
+    let geometry = file("com/example/test/test/shared/geometry.scala");
+    assert!(geometry.contains("type Coord = Int"));
+    assert!(!geometry.contains("case class Point"));
+}
+
+#[test]
+fn test_shared_types_name_clash() {
+    let wit = r#"
+        package test:clash;
+
+        interface a {
+            record error {
+                code: u32,
+            }
+        }
+
+        interface b {
+            record error {
+                message: string,
+            }
+        }
+
+        world test {
+            import a;
+            import b;
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        shared_types: true,
+        ..Default::default()
+    };
+    let mut files = Files::default();
+    let err = opts
+        .build()
+        .generate(&resolve, world, &mut files)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Shared type `Error` is defined in both interface `a` and interface `b`"));
+}
+
+#[test]
+fn test_shared_types_interface_named_types() {
+    let wit = r#"
+        package test:app;
+
+        interface types {
+            record request {
+                url: string,
+            }
+        }
+
+        interface handler {
+            use types.{request};
+
+            handle: func(req: request);
+        }
+
+        world test {
+            import types;
+            import handler;
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+    let generate = |shared_types_object: Option<&str>| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            shared_types: true,
+            shared_types_object: shared_types_object.map(str::to_string),
+            ..Default::default()
+        };
+        let mut files = Files::default();
+        opts.build().generate(&resolve, world, &mut files).map(|()| {
+            files
+                .iter()
+                .map(|(path, contents)| (path.to_string(), std::str::from_utf8(contents).unwrap().to_string()))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // The shared object and the interface's package object would be the same object
+    let err = generate(None).unwrap_err().to_string();
+    assert!(err.contains(
+        "`test:app/types` and the shared types of `test:app` both generate `package object com.example.test.test.app.types`"
+    ));
+
+    let files = generate(Some("shared")).unwrap();
+    let (_, shared) = files
+        .iter()
+        .find(|(path, _)| path == "com/example/test/test/app/shared/package.scala")
+        .unwrap();
+    assert!(shared.contains("package object shared {"));
+    assert!(shared.contains("final case class Request(url: String)"));
+    let (_, handler) = files.iter().find(|(path, _)| path.ends_with("/handler.scala")).unwrap();
+    assert!(handler.contains("def handle(req: com.example.test.test.app.shared.Request): Unit"));
+}
+
+#[test]
+fn test_same_interface_name_in_different_packages() {
+    let wit = r#"
+        package test:app;
+
+        package wasi:io {
+            interface streams {
+                read: func() -> list<u8>;
+            }
+        }
+
+        package other:io {
+            interface streams {
+                write: func(data: list<u8>);
+            }
+        }
+
+        world test {
+            import wasi:io/streams;
+            import other:io/streams;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let mut paths: Vec<_> = files.iter().map(|(path, _)| path.to_string()).collect();
+    paths.sort();
+
+    assert_eq!(
+        paths,
+        [
+            "com/example/test/other/io/streams.scala",
+            "com/example/test/wasi/io/streams.scala",
+        ]
+    );
+
+    let contents: Vec<_> = files.iter().collect();
+    for (path, contents) in contents {
+        let scala_content = std::str::from_utf8(contents).unwrap();
+        if path.contains("wasi") {
+            assert!(scala_content.starts_with("package com.example.test.wasi.io\n"));
+            assert!(scala_content.contains("WitImport(\"wasi:io/streams\", \"read\")"));
+        } else {
+            assert!(scala_content.starts_with("package com.example.test.other.io\n"));
+            assert!(scala_content.contains("WitImport(\"other:io/streams\", \"write\")"));
+        }
+    }
+}
+
+#[test]
+fn test_opts_validate() {
+    use wit_bindgen_scala::Target;
+
+    let valid = Opts {
+        base_package: "com.example".to_string(),
+        drop_method_name: Some("release".to_string()),
+        camel_case_acronyms: vec!["HTTP".to_string()],
+        ..Default::default()
+    };
+    assert!(valid.validate().is_ok());
+
+    let invalid = Opts {
+        base_package: "com..example".to_string(),
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid base package"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        drop_method_name: Some("drop-it".to_string()),
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid drop method name"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        camel_case_acronyms: vec!["".to_string()],
+        ..Default::default()
+    };
+    assert!(invalid.validate().is_err());
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        exports_segment: Some("my.exports".to_string()),
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid exports segment"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        type_annotation: vec!["point=JsonCodec".to_string()],
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid type annotation"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        default_world_name: Some("$root".to_string()),
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid default world name"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        world_namespace: Some("".to_string()),
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid world namespace"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        resource_suffix: Some("-handle".to_string()),
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid resource suffix"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        generated_marker_text: Some("line\nbreak".to_string()),
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid generated marker text"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        shared_types_object: Some("shared types".to_string()),
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid shared types object name"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        target: Target::ScalaNative,
+        emit_jsexport: true,
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("`--emit-jsexport` conflicts with `--target scala-native`"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        type_map: vec!["datetime=java.time.Instant".to_string()],
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("invalid type mapping `datetime=java.time.Instant`"));
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        max_type_depth: Some(0),
+        ..Default::default()
+    };
+    assert!(invalid.validate().is_err());
+
+    let invalid = Opts {
+        base_package: "com.example".to_string(),
+        collapse_single_field_records: true,
+        serial_version_uid: Some(wit_bindgen_scala::SerialVersionUid::Auto),
+        ..Default::default()
+    };
+    assert!(invalid.validate().unwrap_err().to_string().contains("conflicts with `--serial-version-uid`"));
+}
+
+#[test]
+fn test_invalid_opts_fail_generation() {
+    let wit = r#"
+        package test:invalid;
+
+        world test {
+            import log: func(msg: string);
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+
+    let opts = Opts {
+        base_package: "".to_string(),
+        ..Default::default()
+    };
+    let mut files = Files::default();
+    let err = opts.build().generate(&resolve, world, &mut files).unwrap_err();
+    assert!(err.to_string().contains("invalid base package"));
+}
+
+#[test]
+fn test_nested_packages() {
+    let wit = r#"
+        package wasi:io;
+
+        interface streams {
+            record chunk {
+                size: u32,
+            }
+
+            read: func() -> chunk;
+        }
+
+        world test {
+            import streams;
+        }
+    "#;
+
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        nested_packages: true,
+        ..Default::default()
+    };
+    let files = generate_scala_with(wit, opts);
+    let (_, contents) = files
+        .iter()
+        .find(|(path, _)| path.ends_with("wasi/io/streams.scala"))
+        .unwrap();
+    let scala_content = std::str::from_utf8(contents).unwrap();
+
+    assert!(scala_content.starts_with(
+        "package com.example.test\n\npackage wasi {\n  package io {\n\n    package object streams {\n"
+    ));
+    assert!(scala_content.contains("      final case class Chunk(size: scala.scalajs.wit.unsigned.UInt)\n"));
+    assert!(scala_content.ends_with("    }\n  }\n}\n"));
+    assert!(!scala_content.contains("package com.example.test.wasi.io"));
+}
+
+#[test]
+fn test_strict_reports_unsupported_constructs() {
+    let flags: Vec<String> = (0..33).map(|i| format!("flag{}", i)).collect();
+    let wit = format!(
+        r#"
+        package test:strict;
+
+        interface api {{
+            flags many {{ {} }}
+
+            type wide = tuple<{}>;
+
+            resource reader {{
+                next: func() -> stream<u8>;
+            }}
+
+            initial: func(name: string) -> char;
+        }}
+
+        world test {{
+            import api;
+        }}
+    "#,
+        flags.join(", "),
+        vec!["u8"; 23].join(", ")
+    );
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", &wit).unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+    let generate = |strict| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            strict,
+            ..Default::default()
+        };
+        let mut files = Files::default();
+        opts.build().generate(&resolve, world, &mut files)
+    };
+
+    let err = generate(true).unwrap_err().to_string();
+    assert!(err.starts_with("found 4 constructs the runtime can't represent:\n"));
+    assert!(err.contains(
+        "  test:strict/api, type `many`: flags with 33 members don't fit in the `Int` value of a flags case class\n"
+    ));
+    assert!(err.contains(
+        "  test:strict/api, type `wide`: tuple of 23 elements exceeds the 22 elements of `scala.scalajs.wit.Tuple22`\n"
+    ));
+    assert!(err.contains(
+        "  test:strict/api, function `[method]reader.next`: `stream` types are not supported by the runtime"
+    ));
+    assert!(err.contains(
+        "  test:strict/api, function `initial`: `char` values outside the Basic Multilingual Plane are truncated to a UTF-16 `Char`"
+    ));
+
+    // Without `strict`, the same world generates
+    assert!(generate(false).is_ok());
+}
+
+#[test]
+fn test_unsigned_conversions() {
+    let wit = r#"
+        package test:units;
+
+        interface api {
+            record sample {
+                id: u32,
+                offset: u64,
+            }
+
+            measure: func(value: s8) -> sample;
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let generate = |unsigned_conversions| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            unsigned_conversions,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        files
+            .iter()
+            .find(|(path, _)| *path == "com/example/test/UnsignedConversions.scala")
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+    };
+
+    let conversions = generate(true).unwrap();
+    assert!(conversions.starts_with("package com.example.test\n\nimport scala.language.implicitConversions\n"));
+    assert!(conversions.contains(
+        "object UnsignedConversions {\n  implicit def intToUInt(value: Int): scala.scalajs.wit.unsigned.UInt = new scala.scalajs.wit.unsigned.UInt(value)\n  implicit def uintToInt(value: scala.scalajs.wit.unsigned.UInt): Int = value.value\n  implicit def longToULong(value: Long): scala.scalajs.wit.unsigned.ULong = new scala.scalajs.wit.unsigned.ULong(value)\n"
+    ));
+    // Only unsigned types used by the bindings get conversions
+    assert!(!conversions.contains("UByte"));
+    assert!(!conversions.contains("UShort"));
+
+    assert!(generate(false).is_none());
+}
+
+#[test]
+fn test_tuples_in_options_and_results() {
+    let wit = r#"
+        package test:nest;
+
+        interface shapes {
+            record point {
+                x: s32,
+            }
+        }
+
+        interface api {
+            use shapes.{point};
+
+            type located-pair = option<tuple<point, u32>>;
+
+            pair: func() -> option<tuple<u32, string>>;
+            bytes: func() -> result<tuple<u8, u8>, string>;
+            checked: func() -> result<tuple<point, list<point>>, tuple<string, point>>;
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let (_, contents) = files
+        .iter()
+        .find(|(path, _)| path.ends_with("nest/api.scala"))
+        .unwrap();
+    let scala_content = std::str::from_utf8(contents).unwrap();
+
+    assert!(scala_content.contains(
+        "  def pair(): java.util.Optional[scala.scalajs.wit.Tuple2[scala.scalajs.wit.unsigned.UInt, String]] = "
+    ));
+    assert!(scala_content.contains(
+        "  def bytes(): scala.scalajs.wit.Result[scala.scalajs.wit.Tuple2[scala.scalajs.wit.unsigned.UByte, scala.scalajs.wit.unsigned.UByte], String] = "
+    ));
+    // Tuple elements from another interface are qualified
+    assert!(scala_content.contains(
+        "  type LocatedPair = java.util.Optional[scala.scalajs.wit.Tuple2[com.example.test.test.nest.shapes.Point, scala.scalajs.wit.unsigned.UInt]]\n"
+    ));
+    assert!(scala_content.contains(
+        "  def checked(): scala.scalajs.wit.Result[scala.scalajs.wit.Tuple2[com.example.test.test.nest.shapes.Point, Array[com.example.test.test.nest.shapes.Point]], scala.scalajs.wit.Tuple2[String, com.example.test.test.nest.shapes.Point]] = "
+    ));
+}
+
+#[test]
+fn test_type_only_export_package_object() {
+    let wit = r#"
+        package test:only;
+
+        interface shapes {
+            record point {
+                x: s32,
+            }
+        }
+
+        interface api {
+            use shapes.{point};
+
+            origin: func() -> point;
+        }
+
+        world test {
+            export shapes;
+            export api;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let file = |name: &str| {
+        files
+            .iter()
+            .find(|(path, _)| path.ends_with(name))
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .unwrap()
+    };
+
+    let shapes = file("exports/test/only/shapes.scala");
+    assert!(shapes.contains("package object shapes {\n"));
+    assert!(shapes.contains("  final case class Point(x: Int)\n"));
+    assert!(!shapes.contains("WitExportInterface"));
+    assert!(!shapes.contains("trait Shapes"));
+
+    // Interfaces with functions keep the trait, referencing the package object
+    let api = file("exports/test/only/api.scala");
+    assert!(api.contains("@scala.scalajs.wit.annotation.WitExportInterface\ntrait Api {\n"));
+    assert!(api.contains("  def origin(): com.example.test.exports.test.only.shapes.Point\n"));
+}
+
+#[test]
+fn test_function_name_collisions() {
+    let generate = |wit: &str| {
+        let mut resolve = Resolve::default();
+        let pkg = resolve.push_str("test.wit", wit).unwrap();
+        let world = resolve.select_world(&[pkg], None).unwrap();
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            ..Default::default()
+        };
+        let mut files = Files::default();
+        opts.build().generate(&resolve, world, &mut files).map_err(|err| err.to_string())
+    };
+
+    let err = generate(
+        r#"
+        package test:names;
+
+        interface api {
+            get-x: func() -> u32;
+            get-X: func() -> u32;
+        }
+
+        world test {
+            import api;
+        }
+    "#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        "failed to generate `com/example/test/test/names/api.scala`: functions `get-x` and `get-X` of `test:names/api` both generate `def getX`"
+    );
+
+    let err = generate(
+        r#"
+        package test:names;
+
+        interface api {
+            resource conn {
+                read-all: func();
+                read-ALL: func();
+            }
+        }
+
+        world test {
+            import api;
+        }
+    "#,
+    )
+    .unwrap_err();
+    assert!(err.ends_with("functions `read-all` and `read-ALL` of resource `conn` both generate `def readAll`"));
+}
+
+#[test]
+fn test_optimize_option_lists() {
+    let wit = r#"
+        package test:sparse;
+
+        interface api {
+            record point {
+                x: s32,
+            }
+
+            type maybe-count = option<u32>;
+
+            samples: func() -> list<option<f64>>;
+            counts: func(counts: list<maybe-count>);
+            names: func() -> list<option<string>>;
+            points: func() -> list<option<point>>;
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let generate = |optimize_option_lists| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            optimize_option_lists,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let (_, contents) = files.iter().next().unwrap();
+        std::str::from_utf8(contents).unwrap().to_string()
+    };
+
+    let scala_content = generate(true);
+    assert!(scala_content.contains("  def samples(): scala.scalajs.wit.OptionArray[Double] = "));
+    assert!(scala_content.contains(
+        "  def counts(counts: scala.scalajs.wit.OptionArray[scala.scalajs.wit.unsigned.UInt]): Unit = "
+    ));
+    // Only options of primitives other than strings are unboxed
+    assert!(scala_content.contains("  def names(): Array[java.util.Optional[String]] = "));
+    assert!(scala_content.contains("  def points(): Array[java.util.Optional[Point]] = "));
+
+    let scala_content = generate(false);
+    assert!(scala_content.contains("  def samples(): Array[java.util.Optional[Double]] = "));
+    assert!(!scala_content.contains("OptionArray"));
+}
+
+#[test]
+fn test_record_field_names() {
+    let wit = r#"
+        package test:fields;
+
+        interface api {
+            record entry {
+                created-at: u64,
+                %type: string,
+                size: u32,
+            }
+
+            record empty {}
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let generate = |record_field_names| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            record_field_names,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let (_, contents) = files.iter().next().unwrap();
+        std::str::from_utf8(contents).unwrap().to_string()
+    };
+
+    let scala_content = generate(true);
+    assert!(scala_content.contains(
+        "  object Entry {\n    val fieldNames: Array[(String, String)] = Array((\"createdAt\", \"created-at\"), (\"type\", \"type\"), (\"size\", \"size\"))\n  }\n"
+    ));
+    assert!(scala_content.contains("  object Empty {\n    val fieldNames: Array[(String, String)] = Array()\n  }\n"));
+
+    assert!(!generate(false).contains("fieldNames"));
+}
+
+#[test]
+fn test_world_namespace() {
+    let wit = r#"
+        package test:both@1.0.0;
+
+        world test {
+            import log: func(msg: string);
+            export run: func();
+        }
+    "#;
+
+    let generate = |world_namespace: Option<&str>| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            world_namespace: world_namespace.map(str::to_string),
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        files
+            .iter()
+            .map(|(_, contents)| std::str::from_utf8(contents).unwrap().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    // Derived from the world's package by default
+    let scala_content = generate(None);
+    assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitImport(\"test:both/$root@1.0.0\", \"log\")"));
+    assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitExport(\"test:both/$root@1.0.0\", \"run\")"));
+
+    let scala_content = generate(Some("test:both"));
+    assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitImport(\"test:both\", \"log\")"));
+    assert!(scala_content.contains("@scala.scalajs.wit.annotation.WitExport(\"test:both\", \"run\")"));
+    assert!(!scala_content.contains("$root"));
+}
+
+#[test]
+fn test_resource_type_aliases() {
+    let wit = r#"
+        package test:alias;
+
+        interface streams {
+            resource input-stream {
+                read: func(len: u64) -> list<u8>;
+            }
+        }
+
+        interface api {
+            use streams.{input-stream};
+
+            type in = input-stream;
+
+            resource local {}
+
+            type mine = local;
+
+            consume: func(source: borrow<in>, other: in) -> in;
+            keep: func(m: mine) -> mine;
+        }
+
+        world test {
+            import api;
+        }
+    "#;
+
+    let files = generate_scala(wit);
+    let (_, contents) = files
+        .iter()
+        .find(|(path, _)| path.ends_with("alias/api.scala"))
+        .unwrap();
+    let scala_content = std::str::from_utf8(contents).unwrap();
+
+    // Aliases are defined as aliases of the resource trait
+    assert!(scala_content.contains("  type In = com.example.test.test.alias.streams.InputStream\n"));
+    assert!(scala_content.contains("  type Mine = Local\n"));
+    assert!(scala_content.contains("  trait Local extends java.lang.AutoCloseable {\n"));
+
+    // Uses of the aliases, owned or borrowed, resolve to the resource trait
+    assert!(scala_content.contains(
+        "  def consume(source: com.example.test.test.alias.streams.InputStream, other: com.example.test.test.alias.streams.InputStream): com.example.test.test.alias.streams.InputStream = "
+    ));
+    assert!(scala_content.contains("  def keep(m: Local): Local = "));
+}
+
+#[test]
+fn test_emit_test_skeleton() {
+    let wit = r#"
+        package test:skel;
+
+        interface api {
+            ping: func() -> u32;
+            reset-all: func(n: u32);
+
+            resource session {
+                close-now: func();
+            }
+        }
+
+        world test {
+            export api;
+            export run: func();
+        }
+    "#;
+
+    let generate = |emit_test_skeleton| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            emit_test_skeleton,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        files
+            .iter()
+            .map(|(path, contents)| (path.to_string(), std::str::from_utf8(contents).unwrap().to_string()))
+            .collect::<Vec<_>>()
+    };
+    let file = |files: &[(String, String)], path: &str| {
+        files
+            .iter()
+            .find(|(file_path, _)| file_path == path)
+            .map(|(_, contents)| contents.clone())
+            .unwrap()
+    };
+
+    let files = generate(Some(TestFramework::Munit));
+    assert_eq!(
+        file(&files, "com/example/test/exports/test/skel/ApiSuite.scala"),
+        "package com.example.test.exports.test.skel\n\n/** Tests of the `Api` implementation. */\nclass ApiSuite extends munit.FunSuite {\n  test(\"ping\") {\n    ???\n  }\n\n  test(\"resetAll\") {\n    ???\n  }\n}\n"
+    );
+    assert!(file(&files, "com/example/test/exports/test/TestSuite.scala")
+        .contains("class TestSuite extends munit.FunSuite {\n  test(\"run\") {\n"));
+
+    let files = generate(Some(TestFramework::ScalaTest));
+    let spec = file(&files, "com/example/test/exports/test/skel/ApiSpec.scala");
+    assert!(spec.contains("class ApiSpec extends org.scalatest.funsuite.AnyFunSuite {\n"));
+    assert_eq!(spec.matches("  test(").count(), 2);
+
+    let files = generate(None);
+    assert!(!files.iter().any(|(path, _)| path.ends_with("Suite.scala") || path.ends_with("Spec.scala")));
+}
+
+#[test]
+fn test_generated_marker() {
+    let wit = r#"
+        package test:marker;
+
+        interface api {
+            ping: func();
+        }
+
+        world test {
+            import api;
+            export api;
+        }
+    "#;
+
+    let generate = |opts: Opts| {
+        let files = generate_scala_with(wit, opts);
+        files
+            .iter()
+            .map(|(path, contents)| (path.to_string(), std::str::from_utf8(contents).unwrap().to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    let files = generate(Opts {
+        base_package: "com.example.test".to_string(),
+        generated_marker: true,
+        emit_gitattributes: true,
+        ..Default::default()
+    });
+    let scala_files: Vec<_> = files.iter().filter(|(path, _)| path.ends_with(".scala")).collect();
+    assert_eq!(scala_files.len(), 2);
+    for (_, contents) in scala_files {
+        assert!(contents.starts_with("// GENERATED CODE - DO NOT EDIT\npackage com.example.test."));
+    }
+    assert!(files.contains(&(".gitattributes".to_string(), "* linguist-generated=true\n".to_string())));
+
+    let files = generate(Opts {
+        base_package: "com.example.test".to_string(),
+        generated_marker: true,
+        generated_marker_text: Some("Generated from test.wit".to_string()),
+        ..Default::default()
+    });
+    assert!(files.iter().all(|(_, contents)| contents.starts_with("// Generated from test.wit\n")));
+    assert!(!files.iter().any(|(path, _)| path == ".gitattributes"));
+
+    let files = generate(Opts {
+        base_package: "com.example.test".to_string(),
+        ..Default::default()
+    });
+    assert!(files.iter().all(|(_, contents)| contents.starts_with("package ")));
+}
+
+#[test]
+fn test_type_map() {
+    let wit = r#"
+        package test:mapped@1.0.0;
+
+        interface clock {
+            record datetime {
+                seconds: u64,
+                nanoseconds: u32,
+            }
+
+            record duration {
+                seconds: u64,
+            }
+
+            now: func() -> datetime;
+        }
+
+        interface timer {
+            use clock.{datetime};
+
+            set: func(at: datetime) -> option<datetime>;
+        }
+
+        world test {
+            import clock;
+            import timer;
+        }
+    "#;
+
+    for mapping in ["test:mapped/clock.datetime", "test:mapped/clock@1.0.0.datetime"] {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            type_map: vec![format!("{}=java.time.Instant", mapping)],
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        let file = |suffix: &str| {
+            let (_, contents) = files.iter().find(|(path, _)| path.ends_with(suffix)).unwrap();
+            std::str::from_utf8(contents).unwrap().to_string()
+        };
+        let clock = file("/clock.scala");
+        let timer = file("/timer.scala");
+
+        // The mapped type is referenced instead of being generated
+        assert!(!clock.contains("class Datetime"));
+        assert!(clock.contains("def now(): java.time.Instant"));
+        assert!(timer.contains("type Datetime = java.time.Instant"));
+        assert!(timer.contains("def set(at: java.time.Instant): java.util.Optional[java.time.Instant]"));
+        // Other types are still generated
+        assert!(clock.contains("final case class Duration("));
+    }
+}
+
+#[test]
+fn test_empty_interfaces() {
+    let wit = r#"
+        package test:empty;
+
+        interface nothing {}
+
+        interface api {
+            ping: func();
+        }
+
+        world test {
+            import nothing;
+            import api;
+            export nothing;
+        }
+    "#;
+
+    let generate = |skip_empty_interfaces: bool| {
+        let opts = Opts {
+            base_package: "com.example.test".to_string(),
+            skip_empty_interfaces,
+            ..Default::default()
+        };
+        let files = generate_scala_with(wit, opts);
+        files
+            .iter()
+            .map(|(path, contents)| (path.to_string(), std::str::from_utf8(contents).unwrap().to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    // Empty interfaces get a placeholder explaining why there is nothing in them
+    let files = generate(false);
+    assert_eq!(files.len(), 3);
+    for exports in ["", "exports/"] {
+        let path = format!("com/example/test/{}test/empty/nothing.scala", exports);
+        let (_, contents) = files.iter().find(|(file, _)| *file == path).unwrap();
+        assert!(contents.ends_with(
+            "package object nothing {\n  // `test:empty/nothing` defines no types, functions or resources\n\n}\n"
+        ));
+    }
+
+    let files = generate(true);
+    let paths: Vec<_> = files.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(paths, ["com/example/test/test/empty/api.scala"]);
+}
+
+#[test]
+fn test_report_unused_types() {
+    use wit_bindgen_scala::Scala;
+
+    let wit = r#"
+        package test:unused;
+
+        interface shapes {
+            record point {
+                x: s32,
+            }
+
+            record size {
+                width: u32,
+            }
+
+            record color {
+                rgb: u32,
+            }
+        }
+
+        interface canvas {
+            use shapes.{point};
+
+            draw: func(at: point);
+        }
+
+        interface layout {
+            use shapes.{size};
+
+            measure: func() -> size;
+        }
+
+        world painter {
+            import canvas;
+        }
+
+        world designer {
+            import layout;
+        }
+    "#;
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str("test.wit", wit).unwrap();
+    let painter = resolve.select_world(&[pkg], Some("painter")).unwrap();
+    let designer = resolve.select_world(&[pkg], Some("designer")).unwrap();
+    let opts = Opts {
+        base_package: "com.example.test".to_string(),
+        report_unused_types: true,
+        ..Default::default()
+    };
+    let report = |files: &Files| {
+        let (_, bytes) = files.iter().find(|(path, _)| *path == "unused-types.txt").unwrap();
+        std::str::from_utf8(bytes).unwrap().to_string()
+    };
+
+    let mut files = Files::default();
+    opts.clone().build().generate(&resolve, painter, &mut files).unwrap();
+    assert_eq!(report(&files), "test:unused/shapes#size\ntest:unused/shapes#color\n");
+
+    // With several worlds, a type used by any of them is not reported
+    let mut files = Files::default();
+    Scala::new(opts).generate_all(&resolve, &[painter, designer], &mut files).unwrap();
+    assert_eq!(files.iter().filter(|(path, _)| *path == "unused-types.txt").count(), 1);
+    assert_eq!(report(&files), "test:unused/shapes#color\n");
 }
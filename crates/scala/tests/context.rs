@@ -1,4 +1,5 @@
-use wit_bindgen_core::wit_parser::{Function, FunctionKind, Resolve, Type};
+use wit_bindgen_core::wit_parser::{Docs, Function, FunctionKind, Handle, Resolve, Type, TypeDef, TypeDefKind, TypeOwner};
+use wit_bindgen_scala::context::{format_docs, markdown_to_scaladoc, normalize_output};
 use wit_bindgen_scala::{Opts, ScalaContext};
 
 #[test]
@@ -6,6 +7,7 @@ fn test_primitive_types() {
     let mut ctx = ScalaContext::new(&Opts {
         base_package: "test".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     // Test with fully qualified names
@@ -41,6 +43,7 @@ fn test_keyword_escaping() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "test".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     assert_eq!(ctx.escape_keyword("type"), "`type`");
@@ -54,6 +57,7 @@ fn test_name_conversions() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "test".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     assert_eq!(ctx.to_camel_case("kebab-case-name"), "kebabCaseName");
@@ -67,6 +71,10 @@ fn test_name_conversions() {
     // Pure keywords need escaping (camelCase keeps it lowercase, PascalCase capitalizes it)
     assert_eq!(ctx.to_camel_case("type"), "`type`");
     assert_eq!(ctx.to_pascal_case("class"), "Class"); // "Class" is not a keyword
+
+    // Package segments can't be backticked in file paths, so keywords get a suffix
+    assert_eq!(ctx.to_snake_case("package"), "package_");
+    assert_eq!(ctx.to_snake_case("package-name"), "package_name");
 }
 
 #[test]
@@ -74,6 +82,7 @@ fn test_render_function_import() {
     let mut ctx = ScalaContext::new(&Opts {
         base_package: "test".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let resolve = Resolve::default();
@@ -106,6 +115,7 @@ fn test_render_function_export() {
     let mut ctx = ScalaContext::new(&Opts {
         base_package: "test".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let resolve = Resolve::default();
@@ -132,3 +142,222 @@ fn test_render_function_export() {
     assert!(result.contains("): Unit"));
     assert!(!result.contains("native")); // Export functions don't have native marker
 }
+
+#[test]
+fn test_camel_case_acronyms() {
+    let ctx = ScalaContext::new(&Opts {
+        base_package: "test".to_string(),
+        camel_case_acronyms: vec!["HTTP".to_string(), "URL".to_string()],
+        ..Default::default()
+    });
+
+    assert_eq!(ctx.to_pascal_case("http-api"), "HTTPApi");
+    assert_eq!(ctx.to_pascal_case("io-url"), "IoURL");
+    assert_eq!(ctx.to_camel_case("http-api"), "httpApi");
+    assert_eq!(ctx.to_camel_case("get-url"), "getURL");
+
+    // Without configured acronyms, heck's behavior is kept
+    let ctx = ScalaContext::new(&Opts {
+        base_package: "test".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(ctx.to_pascal_case("http-api"), "HttpApi");
+}
+
+#[test]
+fn test_normalize_output() {
+    let input = "package a  \n\n\n\n\nobject b {\t\n\n  val c = 1 \n\n}\n";
+    assert_eq!(
+        normalize_output(input),
+        "package a\n\nobject b {\n\n  val c = 1\n\n}\n"
+    );
+}
+
+#[test]
+fn test_markdown_to_scaladoc() {
+    assert_eq!(
+        markdown_to_scaladoc("See [the spec](https://example.com/spec) for details."),
+        "See [[https://example.com/spec the spec]] for details."
+    );
+    assert_eq!(
+        markdown_to_scaladoc("Returns a [stream](#input-stream)."),
+        "Returns a [[input-stream stream]]."
+    );
+    assert_eq!(
+        markdown_to_scaladoc("Call `read([a](b))` first, [not a link] (x)."),
+        "Call `read([a](b))` first, [not a link] (x)."
+    );
+    assert_eq!(markdown_to_scaladoc("Plain text."), "Plain text.");
+}
+
+#[test]
+fn test_format_docs_converts_markdown() {
+    let docs = Docs {
+        contents: Some("Reads from a [stream](#input-stream).\nUse `len` to limit.".to_string()),
+    };
+    assert_eq!(
+        format_docs(&docs),
+        "/** Reads from a [[input-stream stream]].\n *  Use `len` to limit.\n */\n"
+    );
+}
+
+#[test]
+fn test_format_docs_code_blocks() {
+    let docs = Docs {
+        contents: Some(
+            "Reads a [stream](#input-stream).\n\n  ```scala\n  val s = [a](b)\n  s.foreach { x =>\n    println(x)\n  }\n  ```\nDone."
+                .to_string(),
+        ),
+    };
+    assert_eq!(
+        format_docs(&docs),
+        "/** Reads a [[input-stream stream]].\n *\n *  {{{\n *  val s = [a](b)\n *  s.foreach { x =>\n *    println(x)\n *  }\n *  }}}\n *  Done.\n */\n"
+    );
+}
+
+#[test]
+fn test_cached_type_rendering_matches_uncached() {
+    let mut resolve = Resolve::default();
+    resolve
+        .push_str(
+            "test.wit",
+            r#"
+                package test:cache;
+
+                interface shapes {
+                    record point {
+                        x: s32,
+                        y: s32,
+                    }
+                }
+
+                interface paths {
+                    use shapes.{point};
+
+                    type segment = tuple<point, point>;
+
+                    record path {
+                        start: point,
+                        stop: point,
+                        segments: list<segment>,
+                        waypoints: list<option<point>>,
+                    }
+                }
+            "#,
+        )
+        .unwrap();
+    let opts = Opts {
+        base_package: "test".to_string(),
+        ..Default::default()
+    };
+
+    let mut cached = ScalaContext::new(&opts);
+    for (interface_id, _) in resolve.interfaces.iter() {
+        cached.set_current_interface(Some(interface_id));
+        for _ in 0..3 {
+            for (type_id, _) in resolve.types.iter() {
+                let mut uncached = ScalaContext::new(&opts);
+                uncached.set_current_interface(Some(interface_id));
+                assert_eq!(
+                    cached.render_type(&resolve, &Type::Id(type_id)),
+                    uncached.render_type(&resolve, &Type::Id(type_id))
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_render_typedef_in() {
+    let mut resolve = Resolve::default();
+    resolve
+        .push_str(
+            "test.wit",
+            r#"
+                package test:standalone;
+
+                interface shapes {
+                    record point {
+                        x: s32,
+                        y: s32,
+                    }
+                }
+
+                interface paths {
+                    use shapes.{point};
+
+                    record segment {
+                        start: point,
+                        stop: point,
+                    }
+                }
+            "#,
+        )
+        .unwrap();
+    let interface = |name: &str| {
+        resolve
+            .interfaces
+            .iter()
+            .find(|(_, iface)| iface.name.as_deref() == Some(name))
+            .map(|(id, _)| id)
+            .unwrap()
+    };
+    let (shapes, paths) = (interface("shapes"), interface("paths"));
+    let point = resolve.interfaces[shapes].types["point"];
+    let segment = resolve.interfaces[paths].types["segment"];
+
+    let mut ctx = ScalaContext::new(&Opts {
+        base_package: "test".to_string(),
+        ..Default::default()
+    });
+    ctx.set_current_interface(Some(shapes));
+
+    assert!(
+        ctx.render_typedef_in(&resolve, segment, paths)
+            .contains("final case class Segment(start: test.test.standalone.shapes.Point, stop: test.test.standalone.shapes.Point)")
+    );
+
+    // The previous interface is restored
+    assert_eq!(ctx.render_type(&resolve, &Type::Id(point)), "Point");
+}
+
+#[test]
+fn test_lease_borrowed_results() {
+    let mut ctx = ScalaContext::new(&Opts {
+        base_package: "test".to_string(),
+        lease_borrowed_results: true,
+        ..Default::default()
+    });
+
+    let mut resolve = Resolve::default();
+    let mut alloc = |kind| {
+        resolve.types.alloc(TypeDef {
+            name: None,
+            kind,
+            owner: TypeOwner::None,
+            docs: Default::default(),
+            stability: Default::default(),
+        })
+    };
+    let node_id = alloc(TypeDefKind::Resource);
+    let own_id = alloc(TypeDefKind::Handle(Handle::Own(node_id)));
+    let borrow_id = alloc(TypeDefKind::Handle(Handle::Borrow(node_id)));
+    resolve.types[node_id].name = Some("node".to_string());
+
+    // WIT itself rejects borrows in results, but the runtime may still hand
+    // them out
+    let function = |name: &str, result| Function {
+        name: name.to_string(),
+        kind: FunctionKind::Freestanding,
+        params: vec![("of".to_string(), Type::Id(borrow_id))],
+        result: Some(Type::Id(result)),
+        docs: Default::default(),
+        stability: Default::default(),
+    };
+
+    let borrowed = ctx.render_function(&resolve, &function("current", borrow_id), true, "test:tree/nodes");
+    assert!(borrowed.contains("def current(of: Node): scala.scalajs.wit.Lease[Node] = scala.scalajs.wit.native"));
+
+    let owned = ctx.render_function(&resolve, &function("clone-node", own_id), true, "test:tree/nodes");
+    assert!(owned.contains("def cloneNode(of: Node): Node = scala.scalajs.wit.native"));
+}
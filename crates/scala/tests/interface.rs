@@ -1,11 +1,12 @@
 use wit_bindgen_scala::{Opts, ScalaContext};
-use wit_bindgen_scala::interface::{get_package_path, get_interface_file_path};
+use wit_bindgen_scala::interface::{get_package_path, get_interface_file_path, package_for};
 
 #[test]
 fn test_get_package_path_import() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "com.example".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_package_path(&ctx, "wasi:io/streams@0.2.0", true);
@@ -17,6 +18,7 @@ fn test_get_package_path_import_kebab() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "com.example".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_package_path(&ctx, "scala-wasm:scala-wasm/foo-bar@0.2.0", true);
@@ -28,6 +30,7 @@ fn test_get_package_path_export() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "com.example".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_package_path(&ctx, "my:app/handler@1.0.0", false);
@@ -39,6 +42,7 @@ fn test_get_interface_file_path_import() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "com.example".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_interface_file_path(&ctx, "wasi:io/streams@0.2.0", "streams", true);
@@ -50,6 +54,7 @@ fn test_get_interface_file_path_export() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "com.example".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_interface_file_path(&ctx, "my:app/handler@1.0.0", "handler", false);
@@ -61,6 +66,7 @@ fn test_get_interface_file_path_with_kebab_case() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "com.example".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_interface_file_path(&ctx, "my-org:my-app/my-handler@1.0.0", "my-handler", true);
@@ -72,8 +78,40 @@ fn test_get_package_path_no_version() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "test".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_package_path(&ctx, "example:api/basic", true);
     assert_eq!(path, "test.example.api");
 }
+
+#[test]
+fn test_package_for() {
+    let opts = Opts {
+        base_package: "com.example".to_string(),
+        ..Default::default()
+    };
+
+    assert_eq!(package_for(&opts, "wasi:io/streams@0.2.0", true), "com.example.wasi.io");
+    assert_eq!(
+        package_for(&opts, "wasi:io/streams@0.2.0", false),
+        "com.example.exports.wasi.io"
+    );
+}
+
+#[test]
+fn test_custom_exports_segment() {
+    let ctx = ScalaContext::new(&Opts {
+        base_package: "com.example".to_string(),
+        exports_segment: Some("guest".to_string()),
+        ..Default::default()
+    });
+
+    assert_eq!(get_package_path(&ctx, "my:app/handler@1.0.0", false), "com.example.guest.my.app");
+    assert_eq!(
+        get_interface_file_path(&ctx, "my:app/handler@1.0.0", "handler", false),
+        "com/example/guest/my/app/handler.scala"
+    );
+    // Imports are unaffected
+    assert_eq!(get_package_path(&ctx, "my:app/handler@1.0.0", true), "com.example.my.app");
+}
@@ -1,12 +1,16 @@
-use wit_bindgen_core::wit_parser::{Function, FunctionKind, Resolve, Type, TypeDef, TypeDefKind, TypeOwner};
+use wit_bindgen_core::wit_parser::{Function, FunctionKind, Handle, Resolve, Type, TypeDef, TypeDefKind, TypeOwner};
 use wit_bindgen_scala::{Opts, ScalaContext};
-use wit_bindgen_scala::resource::{render_resource_method, render_resource_constructor, render_resource_drop_method};
+use wit_bindgen_scala::resource::{
+    render_exported_resource, render_exported_resource_method, render_imported_resource, render_resource_constructor,
+    render_resource_drop_method, render_resource_method,
+};
 
 #[test]
 fn test_render_resource_method() {
     let mut ctx = ScalaContext::new(&Opts {
         base_package: "test".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let mut resolve = Resolve::default();
@@ -44,6 +48,7 @@ fn test_render_resource_constructor() {
     let mut ctx = ScalaContext::new(&Opts {
         base_package: "test".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let mut resolve = Resolve::default();
@@ -77,7 +82,152 @@ fn test_render_resource_constructor() {
 
 #[test]
 fn test_render_resource_drop_method() {
-    let result = render_resource_drop_method();
+    let result = render_resource_drop_method("close");
     assert!(result.contains("@scala.scalajs.wit.annotation.WitResourceDrop"));
     assert!(result.contains("def close(): Unit = scala.scalajs.wit.native"));
+    assert!(!result.contains("override def close()"));
+}
+
+#[test]
+fn test_render_resource_drop_method_custom_name() {
+    let result = render_resource_drop_method("release");
+    assert!(result.contains("@scala.scalajs.wit.annotation.WitResourceDrop\n  def release(): Unit = scala.scalajs.wit.native"));
+    assert!(result.contains("override def close(): Unit = release()"));
+}
+
+#[test]
+fn test_render_marker_resource() {
+    let mut ctx = ScalaContext::new(&Opts {
+        base_package: "test".to_string(),
+        ..Default::default()
+    });
+
+    let mut resolve = Resolve::default();
+    resolve
+        .push_str(
+            "test.wit",
+            r#"
+                package test:markers;
+
+                interface tokens {
+                    resource token;
+                }
+            "#,
+        )
+        .unwrap();
+    let (token_id, _) = resolve
+        .types
+        .iter()
+        .find(|(_, ty)| ty.name.as_deref() == Some("token"))
+        .unwrap();
+
+    let result = render_imported_resource(&mut ctx, &resolve, token_id, "test:markers/tokens");
+
+    assert!(result.contains("trait Token extends java.lang.AutoCloseable {"));
+    assert!(result.contains("def close(): Unit = scala.scalajs.wit.native"));
+    assert!(!result.contains("object Token"));
+    assert_eq!(result.matches('{').count(), result.matches('}').count());
+    assert!(result.trim_end().ends_with('}'));
+}
+
+#[test]
+fn test_render_scoped_borrow_method() {
+    let mut ctx = ScalaContext::new(&Opts {
+        base_package: "test".to_string(),
+        scoped_borrows: true,
+        ..Default::default()
+    });
+
+    let mut resolve = Resolve::default();
+    let node_id = resolve.types.alloc(TypeDef {
+        name: Some("node".to_string()),
+        kind: TypeDefKind::Resource,
+        owner: TypeOwner::None,
+        docs: Default::default(),
+        stability: Default::default(),
+    });
+    let borrow_id = resolve.types.alloc(TypeDef {
+        name: None,
+        kind: TypeDefKind::Handle(Handle::Borrow(node_id)),
+        owner: TypeOwner::None,
+        docs: Default::default(),
+        stability: Default::default(),
+    });
+
+    // WIT itself rejects borrows in results, but the runtime may still hand
+    // them out for exported resources
+    let func = Function {
+        name: "[method]node.parent".to_string(),
+        kind: FunctionKind::Method(node_id),
+        params: vec![("self".to_string(), Type::Id(borrow_id)), ("depth".to_string(), Type::U32)],
+        result: Some(Type::Id(borrow_id)),
+        docs: Default::default(),
+        stability: Default::default(),
+    };
+
+    let exported = render_exported_resource_method(&mut ctx, &resolve, "parent", &func);
+    assert!(exported.contains("@scala.scalajs.wit.annotation.WitResourceMethod(\"parent\")"));
+    assert!(exported.contains("def withParent[R](depth: scala.scalajs.wit.unsigned.UInt)(f: Node => R): R\n"));
+
+    // Imported methods keep the WIT signature the runtime lowers
+    let imported = render_resource_method(&mut ctx, &resolve, "parent", &func);
+    assert!(imported.contains("def parent(depth: scala.scalajs.wit.unsigned.UInt): Node = scala.scalajs.wit.native"));
+
+    // Without the flag the borrow is returned directly
+    let mut ctx = ScalaContext::new(&Opts {
+        base_package: "test".to_string(),
+        ..Default::default()
+    });
+    let plain = render_exported_resource_method(&mut ctx, &resolve, "parent", &func);
+    assert!(plain.contains("def parent(depth: scala.scalajs.wit.unsigned.UInt): Node\n"));
+}
+
+#[test]
+fn test_scoped_borrow_method_name_collision() {
+    let mut resolve = Resolve::default();
+    let pkg = resolve
+        .push_str(
+            "test.wit",
+            r#"
+                package test:tree;
+
+                interface nodes {
+                    resource node {
+                        parent: func() -> node;
+                        with-parent: func();
+                    }
+                }
+            "#,
+        )
+        .unwrap();
+    let interface_id = resolve.packages[pkg].interfaces["nodes"];
+    let node_id = resolve.interfaces[interface_id].types["node"];
+    let borrow_id = resolve.types.alloc(TypeDef {
+        name: None,
+        kind: TypeDefKind::Handle(Handle::Borrow(node_id)),
+        owner: TypeOwner::None,
+        docs: Default::default(),
+        stability: Default::default(),
+    });
+    // WIT rejects borrows in results, so the method is changed to return one
+    resolve.interfaces[interface_id]
+        .functions
+        .get_mut("[method]node.parent")
+        .unwrap()
+        .result = Some(Type::Id(borrow_id));
+
+    let mut ctx = ScalaContext::new(&Opts {
+        base_package: "test".to_string(),
+        scoped_borrows: true,
+        ..Default::default()
+    });
+    render_exported_resource(&mut ctx, &resolve, node_id, "test:tree/nodes");
+    assert_eq!(
+        ctx.take_render_error().unwrap(),
+        "functions `parent` and `with-parent` of resource `node` both generate `def withParent`"
+    );
+
+    // Imported methods aren't renamed, so they don't collide
+    render_imported_resource(&mut ctx, &resolve, node_id, "test:tree/nodes");
+    assert!(ctx.take_render_error().is_none());
 }
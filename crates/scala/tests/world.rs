@@ -1,11 +1,13 @@
+use wit_bindgen_core::wit_parser::Resolve;
 use wit_bindgen_scala::{Opts, ScalaContext};
-use wit_bindgen_scala::world::{get_world_package_path, get_world_file_path};
+use wit_bindgen_scala::world::{get_world_namespace, get_world_package_path, get_world_file_path};
 
 #[test]
 fn test_get_world_package_path_import() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "com.example".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_world_package_path(&ctx, "my-world", true);
@@ -17,6 +19,7 @@ fn test_get_world_package_path_export() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "com.example".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_world_package_path(&ctx, "my-world", false);
@@ -28,6 +31,7 @@ fn test_get_world_file_path_import() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "com.example".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_world_file_path(&ctx, "my-world", true);
@@ -39,8 +43,35 @@ fn test_get_world_file_path_export() {
     let ctx = ScalaContext::new(&Opts {
         base_package: "com.example".to_string(),
         binding_root: None,
+        ..Default::default()
     });
 
     let path = get_world_file_path(&ctx, "my-world", false);
     assert_eq!(path, "com/example/exports/my_world/package.scala");
 }
+
+#[test]
+fn test_get_world_namespace() {
+    let mut resolve = Resolve::default();
+    let pkg = resolve
+        .push_str("test.wit", "package my:app; world my-world {}")
+        .unwrap();
+    let world = resolve.select_world(&[pkg], None).unwrap();
+
+    assert_eq!(get_world_namespace(&resolve, world), "my:app/$root");
+}
+
+#[test]
+fn test_custom_exports_segment() {
+    let ctx = ScalaContext::new(&Opts {
+        base_package: "com.example".to_string(),
+        exports_segment: Some("impl".to_string()),
+        ..Default::default()
+    });
+
+    assert_eq!(get_world_package_path(&ctx, "my-world", false), "com.example.impl.my_world");
+    assert_eq!(
+        get_world_file_path(&ctx, "my-world", false),
+        "com/example/impl/my_world/package.scala"
+    );
+}